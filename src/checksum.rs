@@ -27,7 +27,7 @@ use std::iter::IntoIterator;
 use std::slice::Iter;
 
 use crate::{error::ValidateError, Cache};
-use nom::{combinator::cond, number::complete::be_u32};
+use nom::{combinator::cond, number::complete::be_u32, Parser};
 use runefs::{
     codec::{Buffer, Encoded},
     REFERENCE_TABLE_ID,
@@ -51,6 +51,43 @@ pub struct Entry {
     pub(crate) hash: Vec<u8>,
 }
 
+impl Entry {
+    /// Makes an entry from a crc and version, e.g. ones sent by a client.
+    #[inline]
+    pub fn new(crc: u32, version: u32) -> Self {
+        Self {
+            crc,
+            version,
+            #[cfg(feature = "rs3")]
+            hash: vec![0; 64],
+        }
+    }
+
+    #[allow(missing_docs)]
+    #[inline]
+    pub const fn crc(&self) -> u32 {
+        self.crc
+    }
+
+    #[allow(missing_docs)]
+    #[inline]
+    pub const fn version(&self) -> u32 {
+        self.version
+    }
+}
+
+/// Which parts of an [`Entry`] differ, reported by [`validate_full`](Checksum::validate_full).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum EntryMismatch {
+    /// Only the crc differs.
+    Crc,
+    /// Only the version differs.
+    Version,
+    /// Both the crc and the version differ.
+    Both,
+}
+
 /// Validator for the `Cache`.
 ///
 /// Used to validate cache index files. It contains a list of entries, one entry for each index file.
@@ -62,6 +99,8 @@ pub struct Entry {
 pub struct Checksum {
     index_count: usize,
     entries: Vec<Entry>,
+    /// The index id of every entry, indices that can't be read have no entry.
+    pub(crate) index_ids: Vec<u8>,
 }
 
 impl Checksum {
@@ -71,19 +110,42 @@ impl Checksum {
     /// 
     /// Decoding of a index buffer fails, this is considered a bug.
     pub fn new(cache: &Cache) -> crate::Result<Self> {
+        let indices: Vec<u8> = (0..cache.indices.count())
+            .map(|idx_id| idx_id as u8)
+            .collect();
+
+        Self::with_indices(cache, &indices)
+    }
+
+    /// Generate a checksum containing only the given indices, in the given order.
+    ///
+    /// Indices that can't be read are left out of the checksum.
+    ///
+    /// # Errors
+    ///
+    /// Decoding of a index buffer fails, this is considered a bug.
+    pub fn with_indices(cache: &Cache, indices: &[u8]) -> crate::Result<Self> {
+        let (index_ids, entries) = Self::read_entries(cache, indices)?.into_iter().unzip();
+
         Ok(Self {
-            index_count: cache.indices.count(),
-            entries: Self::entries(cache)?,
+            index_count: indices.len(),
+            entries,
+            index_ids,
         })
     }
 
-    fn entries(cache: &Cache) -> crate::Result<Vec<Entry>> {
-        let entries: Vec<Entry> = (0..cache.indices.count())
-            .filter_map(|idx_id| cache.read(REFERENCE_TABLE_ID, idx_id as u32).ok())
-            .enumerate()
-            .map(|(idx_id, buffer)| -> crate::Result<Entry> {
+    fn read_entries(cache: &Cache, indices: &[u8]) -> crate::Result<Vec<(u8, Entry)>> {
+        let entries: Vec<(u8, Entry)> = indices
+            .iter()
+            .filter_map(|&idx_id| {
+                cache
+                    .read(REFERENCE_TABLE_ID, idx_id as u32)
+                    .ok()
+                    .map(|buffer| (idx_id, buffer))
+            })
+            .map(|(idx_id, buffer)| -> crate::Result<(u8, Entry)> {
                 if buffer.is_empty() || idx_id == 47 {
-                    Ok(Entry::default())
+                    Ok((idx_id, Entry::default()))
                 } else {
                     // let (buffer, size) = if with_rsa {
                     //     be_u8(buffer.as_slice())?
@@ -100,16 +162,19 @@ impl Checksum {
 
                     let checksum = crc32fast::hash(&buffer);
 
-                    let data = buffer.decode()?;
-                    let (_, version) = cond(data[0] >= 6, be_u32)(&data[1..5])?;
+                    let data = cache.decode(buffer)?;
+                    let (_, version) = cond(data[0] >= 6, be_u32).parse(&data[1..5])?;
                     let version = version.unwrap_or(0);
 
-                    Ok(Entry {
-                        crc: checksum,
-                        version,
-                        #[cfg(feature = "rs3")]
-                        hash,
-                    })
+                    Ok((
+                        idx_id,
+                        Entry {
+                            crc: checksum,
+                            version,
+                            #[cfg(feature = "rs3")]
+                            hash,
+                        },
+                    ))
                 }
             })
             .filter_map(crate::Result::ok)
@@ -201,6 +266,69 @@ impl Checksum {
         Ok(())
     }
 
+    /// Validates the given crcs from the client and collects every mismatch instead of
+    /// stopping at the first one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `(index, expected, actual)` tuple for each index whose crc doesn't match.
+    /// If the client sent too many or too few crcs the missing side is reported as `0`.
+    pub fn validate_crcs_detailed(&self, crcs: &[u32]) -> Result<(), Vec<(usize, u32, u32)>> {
+        let len = self.entries.len().max(crcs.len());
+        let mismatches: Vec<(usize, u32, u32)> = (0..len)
+            .map(|index| {
+                let internal = self.entries.get(index).map_or(0, |entry| entry.crc);
+                let external = crcs.get(index).copied().unwrap_or(0);
+                (index, internal, external)
+            })
+            .filter(|(_, internal, external)| internal != external)
+            .collect();
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+
+    /// Validates both the crc and the version of every entry and collects every mismatch.
+    ///
+    /// Unlike the crc validators this tells whether an index changed or only its version
+    /// was bumped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the index and the kind of mismatch for each entry that differs. If too many
+    /// or too few entries were given the missing side is reported as [`EntryMismatch::Both`].
+    pub fn validate_full(&self, entries: &[Entry]) -> Result<(), Vec<(usize, EntryMismatch)>> {
+        let len = self.entries.len().max(entries.len());
+        let mismatches: Vec<(usize, EntryMismatch)> = (0..len)
+            .filter_map(|index| {
+                let mismatch = match (self.entries.get(index), entries.get(index)) {
+                    (Some(internal), Some(external)) => {
+                        match (
+                            internal.crc != external.crc,
+                            internal.version != external.version,
+                        ) {
+                            (false, false) => return None,
+                            (true, false) => EntryMismatch::Crc,
+                            (false, true) => EntryMismatch::Version,
+                            (true, true) => EntryMismatch::Both,
+                        }
+                    }
+                    _ => EntryMismatch::Both,
+                };
+                Some((index, mismatch))
+            })
+            .collect();
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+
     #[allow(missing_docs)]
     #[inline]
     pub const fn index_count(&self) -> usize {
@@ -212,6 +340,25 @@ impl Checksum {
     pub fn iter(&self) -> Iter<'_, Entry> {
         self.entries.iter()
     }
+
+    /// The entries in index order, e.g. to write them into a client handshake.
+    #[inline]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The number of entries, indices that couldn't be read are not counted unlike
+    /// [`index_count`](Checksum::index_count).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[allow(missing_docs)]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
 }
 
 /// A struct that holds both keys for RSA encryption.
@@ -328,6 +475,20 @@ impl IntoIterator for Checksum {
     }
 }
 
+/// Builds a checksum from entries that are ordered by index id, e.g. the checksum
+/// table received from an update server.
+impl FromIterator<Entry> for Checksum {
+    fn from_iter<I: IntoIterator<Item = Entry>>(iter: I) -> Self {
+        let entries: Vec<Entry> = iter.into_iter().collect();
+
+        Self {
+            index_count: entries.len(),
+            index_ids: (0..entries.len()).map(|index_id| index_id as u8).collect(),
+            entries,
+        }
+    }
+}
+
 impl<'a> IntoIterator for &'a Checksum {
     type Item = &'a Entry;
     type IntoIter = Iter<'a, Entry>;
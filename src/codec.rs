@@ -0,0 +1,204 @@
+//! Pluggable decompression of cache containers.
+//!
+//! By default containers are decoded by the codec of [`runefs`]. A custom
+//! [`Decompressor`] can be supplied with
+//! [`Cache::with_decompressor`](crate::Cache::with_decompressor) to swap in a
+//! different implementation for some or all compression types.
+//!
+//! Standalone containers can be built and taken apart with [`encode`] and [`decode`].
+
+use std::{fmt, io, io::Write};
+
+use nom::{
+    bytes::complete::take,
+    number::complete::{be_u16, be_u32, be_u8},
+};
+pub use runefs::codec::Compression;
+use runefs::codec::{Buffer, Decoded, Encoded};
+use runefs::error::{CompressionUnsupported, Error as RuneFsError};
+
+/// Decompresses the payload of a container.
+///
+/// Implementations that only want to replace a single compression type can
+/// delegate the others to [`DefaultDecompressor`].
+pub trait Decompressor: Send + Sync + fmt::Debug {
+    /// Decompresses `data`, the payload of a container without its header.
+    ///
+    /// `decompressed_len` is the length announced by the container header, for
+    /// uncompressed containers it equals the length of `data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` can't be decompressed.
+    fn decompress(
+        &self,
+        compression: Compression,
+        data: &[u8],
+        decompressed_len: usize,
+    ) -> crate::Result<Vec<u8>>;
+}
+
+/// Decompresses containers with the codec of [`runefs`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct DefaultDecompressor;
+
+impl Decompressor for DefaultDecompressor {
+    fn decompress(
+        &self,
+        compression: Compression,
+        data: &[u8],
+        decompressed_len: usize,
+    ) -> crate::Result<Vec<u8>> {
+        let mut container = Vec::with_capacity(data.len() + 9);
+        container.push(u8::from(compression));
+        container.extend((data.len() as u32).to_be_bytes());
+        if compression != Compression::None {
+            container.extend((decompressed_len as u32).to_be_bytes());
+        }
+        container.extend(data);
+
+        Ok(Buffer::<Encoded>::from(container).decode()?.finalize())
+    }
+}
+
+/// Compresses `data` into a container.
+///
+/// If a `revision` is given it is appended as a 2-byte trailer after the payload.
+///
+/// # Errors
+///
+/// Returns an error if `data` can't be compressed.
+pub fn encode(
+    compression: Compression,
+    data: &[u8],
+    revision: Option<u16>,
+) -> crate::Result<Vec<u8>> {
+    let mut buffer = Buffer::<Decoded>::from(data).with_compression(compression);
+    if let Some(revision) = revision {
+        buffer = buffer.with_version(revision as i16);
+    }
+
+    Ok(buffer.encode()?.finalize())
+}
+
+/// Same as [`encode`] but compresses with the given `level`.
+///
+/// For gzip the level ranges from 0 (no compression) to 9 (smallest output), [`encode`]
+/// uses 9. For bzip2 the level is the block size in units of 100 kB from 1 to 9,
+/// [`encode`] uses 1. The bzip2 header is not stored in the container and readers
+/// always assume a block size of 1, so higher levels are only accepted for data that fits
+/// in a single 100 kB block. The level is ignored for other compression types.
+///
+/// # Errors
+///
+/// Returns an error if the level is out of range or `data` can't be compressed.
+pub fn encode_with_level(
+    compression: Compression,
+    data: &[u8],
+    revision: Option<u16>,
+    level: u32,
+) -> crate::Result<Vec<u8>> {
+    let payload = match compression {
+        Compression::Gzip => compress_gzip(data, level)?,
+        Compression::Bzip2 => compress_bzip2(data, level)?,
+        _ => return encode(compression, data, revision),
+    };
+
+    let mut container = Vec::with_capacity(payload.len() + 11);
+    container.push(u8::from(compression));
+    container.extend((payload.len() as u32).to_be_bytes());
+    container.extend((data.len() as u32).to_be_bytes());
+    container.extend(payload);
+    if let Some(revision) = revision {
+        container.extend(revision.to_be_bytes());
+    }
+
+    Ok(container)
+}
+
+fn compress_gzip(data: &[u8], level: u32) -> io::Result<Vec<u8>> {
+    if level > 9 {
+        return Err(invalid_level(level));
+    }
+
+    let level = flate2::Compression::new(level);
+    let mut compressor = flate2::write::GzEncoder::new(Vec::with_capacity(data.len()), level);
+    compressor.write_all(data)?;
+
+    compressor.finish()
+}
+
+fn compress_bzip2(data: &[u8], level: u32) -> io::Result<Vec<u8>> {
+    const BLOCK_SIZE: usize = 100_000;
+
+    if !(1..=9).contains(&level) || (level > 1 && data.len() > BLOCK_SIZE) {
+        return Err(invalid_level(level));
+    }
+
+    let level = bzip2::Compression::new(level);
+    let mut compressor = bzip2::write::BzEncoder::new(Vec::with_capacity(data.len()), level);
+    compressor.write_all(data)?;
+    let mut compressed = compressor.finish()?;
+    // The `BZh` magic and block size are restored by the reader.
+    compressed.drain(..4);
+
+    Ok(compressed)
+}
+
+fn invalid_level(level: u32) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("compression level {} is not supported", level),
+    )
+}
+
+/// Decompresses a container, returning its data and revision trailer if it has one.
+///
+/// # Errors
+///
+/// Returns an error if the header is malformed or the payload can't be decompressed.
+pub fn decode(container: &[u8]) -> crate::Result<(Vec<u8>, Option<u16>)> {
+    let container = Container::parse(container)?;
+    let data = DefaultDecompressor.decompress(
+        container.compression,
+        container.payload,
+        container.decompressed_len,
+    )?;
+
+    Ok((data, container.revision))
+}
+
+/// A container split into its header, payload and trailer.
+pub(crate) struct Container<'a> {
+    pub compression: Compression,
+    pub payload: &'a [u8],
+    pub decompressed_len: usize,
+    pub revision: Option<u16>,
+}
+
+impl<'a> Container<'a> {
+    pub fn parse(container: &'a [u8]) -> crate::Result<Self> {
+        let (data, compression) = be_u8(container)?;
+        let compression = Compression::try_from(compression)
+            .map_err(|_| RuneFsError::Compression(CompressionUnsupported))?;
+        let (data, compressed_len) = be_u32(data)?;
+        let (data, decompressed_len) = if compression == Compression::None {
+            (data, compressed_len)
+        } else {
+            be_u32(data)?
+        };
+        let (trailer, payload) = take(compressed_len as usize)(data)?;
+        let revision = if trailer.len() >= 2 {
+            Some(be_u16(trailer)?.1)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            compression,
+            payload,
+            decompressed_len: decompressed_len as usize,
+            revision,
+        })
+    }
+}
@@ -1,25 +1,232 @@
+mod area_def;
+mod enum_def;
+mod frame_def;
+mod hitsplat_def;
+mod identikit_def;
 #[allow(clippy::too_many_lines)]
 mod item_def;
 mod loc_def;
 mod map_def;
+mod model_def;
 mod npc_def;
 #[allow(clippy::too_many_lines)]
 mod obj_def;
+mod overlay_def;
+mod param_def;
+mod sequence_def;
+mod skeleton_def;
+mod spot_anim_def;
+mod sprite_def;
+mod texture_def;
+mod varbit_def;
+mod world_map_def;
 
+pub use area_def::*;
+pub use enum_def::*;
+pub use frame_def::*;
+pub use hitsplat_def::*;
+pub use identikit_def::*;
 pub use item_def::*;
 pub use loc_def::*;
 pub use map_def::*;
+pub use model_def::*;
 pub use npc_def::*;
 pub use obj_def::*;
+pub use overlay_def::*;
+pub use param_def::*;
+pub use sequence_def::*;
+pub use skeleton_def::*;
+pub use spot_anim_def::*;
+pub use sprite_def::*;
+pub use texture_def::*;
+pub use varbit_def::*;
+pub use world_map_def::*;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, io, io::Cursor};
 
-use crate::Cache;
-use runefs::{ArchiveFileGroup, IndexMetadata, REFERENCE_TABLE_ID};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::DecodeWarning,
+    extension::ReadExt,
+    index::ArchiveId,
+    loader::{LoaderOptions, UnknownOpcodePolicy},
+    util, Cache,
+};
+use runefs::error::{Error as RuneFsError, ReadError};
+use runefs::{ArchiveFileData, ArchiveFileGroup, IndexMetadata, REFERENCE_TABLE_ID};
 
 /// Marker trait for definitions.
 pub trait Definition: Sized {
     fn new(id: u16, buffer: &[u8]) -> crate::Result<Self>;
+
+    /// Same as [`new`](Definition::new) but also asserts that the whole buffer was consumed.
+    ///
+    /// Definitions that are terminated by opcode 0 should not have any bytes left after it,
+    /// if they do the decoder is out of sync with the cache.
+    ///
+    /// Only the OSRS opcode definitions check this: areas, enums, hitsplats, identikits,
+    /// items, npcs, objects, overlays, params, sequences, spot animations and varbits. The
+    /// default forwards to `new`, so every other definition accepts trailing bytes. That
+    /// includes fixed layout formats such as models, sprites and maps, and all RS3
+    /// definitions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReadError::TrailingBytes` if any bytes remain after decoding.
+    fn new_strict(id: u16, buffer: &[u8]) -> crate::Result<Self> {
+        Self::new(id, buffer)
+    }
+
+    /// Same as [`new`](Definition::new) but keeps whatever was decoded when decoding fails.
+    ///
+    /// Opcodes with a known payload size are always skipped, but after an unknown opcode
+    /// the decoder can't find the next opcode so the rest of the buffer is skipped.
+    /// Every problem is reported as a warning instead of an error.
+    ///
+    /// Every definition that is made of opcodes keeps the fields decoded before the
+    /// failure. Other formats, like maps and models, return their default on failure.
+    fn new_lenient(id: u16, buffer: &[u8]) -> (Self, Vec<DecodeWarning>)
+    where
+        Self: Default,
+    {
+        match Self::new(id, buffer) {
+            Ok(definition) => (definition, Vec::new()),
+            Err(error) => {
+                let warning = DecodeWarning::Stopped(error.to_string());
+                (Self::default(), vec![warning])
+            }
+        }
+    }
+
+    /// Same as [`new`](Definition::new) but lets the caller decide what happens when an
+    /// unknown opcode is found, see [`UnknownOpcodePolicy`].
+    ///
+    /// Every definition that is made of opcodes decodes with the policy. Other formats,
+    /// like maps and models, have no opcodes to be unknown and decode with `new`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReadError::UnknownOpcode` for unknown opcodes under
+    /// `UnknownOpcodePolicy::Error`, otherwise the same errors as `new`.
+    fn new_with_policy(
+        id: u16,
+        buffer: &[u8],
+        _policy: UnknownOpcodePolicy,
+    ) -> crate::Result<(Self, Vec<DecodeWarning>)> {
+        Ok((Self::new(id, buffer)?, Vec::new()))
+    }
+
+    /// Same as [`new_with_policy`](Definition::new_with_policy) but also decodes opcodes
+    /// whose payload changed between revisions with the layout of `options.revision`.
+    ///
+    /// Only npcs and objects have such opcodes, every other definition decodes the same
+    /// for every revision.
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`new_with_policy`](Definition::new_with_policy).
+    fn new_with_options(
+        id: u16,
+        buffer: &[u8],
+        options: LoaderOptions,
+    ) -> crate::Result<(Self, Vec<DecodeWarning>)> {
+        Self::new_with_policy(id, buffer, options.on_unknown_opcode)
+    }
+}
+
+/// A definition together with the buffer it was decoded from.
+///
+/// Useful for formats that aren't fully decoded yet, the raw buffer can be written back
+/// as is to keep unknown data intact. Works with any loader or fetch function by naming
+/// it as the definition type.
+///
+/// # Examples
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use rscache::Cache;
+/// use rscache::definition::osrs::{FetchDefinition, ItemDefinition, RawDefinition};
+///
+/// # fn main() -> Result<(), rscache::Error> {
+/// # let cache = Cache::new("./data/osrs_cache")?;
+/// let item_defs: HashMap<u16, RawDefinition<ItemDefinition>>
+///     = ItemDefinition::fetch_from_archive(&cache, 2, 10)?;
+///
+/// let whip = &item_defs[&4151];
+/// assert_eq!(whip.definition.name, "Abyssal whip");
+/// assert!(!whip.raw().is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct RawDefinition<D> {
+    pub definition: D,
+    pub raw: Vec<u8>,
+}
+
+impl<D> RawDefinition<D> {
+    /// The buffer the definition was decoded from.
+    #[inline]
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+}
+
+impl<D: Definition + Default> Definition for RawDefinition<D> {
+    fn new(id: u16, buffer: &[u8]) -> crate::Result<Self> {
+        Ok(Self {
+            definition: D::new(id, buffer)?,
+            raw: buffer.to_vec(),
+        })
+    }
+
+    fn new_strict(id: u16, buffer: &[u8]) -> crate::Result<Self> {
+        Ok(Self {
+            definition: D::new_strict(id, buffer)?,
+            raw: buffer.to_vec(),
+        })
+    }
+
+    fn new_lenient(id: u16, buffer: &[u8]) -> (Self, Vec<DecodeWarning>) {
+        let (definition, warnings) = D::new_lenient(id, buffer);
+        let raw_def = Self {
+            definition,
+            raw: buffer.to_vec(),
+        };
+
+        (raw_def, warnings)
+    }
+
+    fn new_with_policy(
+        id: u16,
+        buffer: &[u8],
+        policy: UnknownOpcodePolicy,
+    ) -> crate::Result<(Self, Vec<DecodeWarning>)> {
+        let (definition, warnings) = D::new_with_policy(id, buffer, policy)?;
+        let raw_def = Self {
+            definition,
+            raw: buffer.to_vec(),
+        };
+
+        Ok((raw_def, warnings))
+    }
+
+    fn new_with_options(
+        id: u16,
+        buffer: &[u8],
+        options: LoaderOptions,
+    ) -> crate::Result<(Self, Vec<DecodeWarning>)> {
+        let (definition, warnings) = D::new_with_options(id, buffer, options)?;
+        let raw_def = Self {
+            definition,
+            raw: buffer.to_vec(),
+        };
+
+        Ok((raw_def, warnings))
+    }
 }
 
 /// Adds definition fetching from the cache to every struct that implements `Definition`.
@@ -27,27 +234,43 @@ pub trait Definition: Sized {
 /// The main difference between `fetch_from_index` and `fetch_from_archive`:
 /// - `fetch_from_index` will get only 1 definition from each archive making it a 1:1 relation.
 /// - `fetch_from_archive` will get multiple definitions from each archive making it a N:1 relation
-/// where N is atleast 1.
+///   where N is atleast 1.
 pub trait FetchDefinition: Definition {
     // TODO: finish documentation with example.
     /// Fetches multiple definitions from every archive in the index.
     ///
     /// Note: every archive contains only one definition. (1:1)
     ///
+    /// Archives with a length of zero are skipped instead of decoded.
+    ///
     /// # Errors
     ///
     /// Can return multiple errors: if reading, decoding or parsing definition buffers fail.
+    /// Archive ids that don't fit in a `u16` return `ReadError::ArchiveIdOutOfRange`.
+    /// Unknown opcodes are handled with the policy of the cache, see
+    /// [`Cache::with_unknown_opcode_policy`], and opcodes that changed between revisions
+    /// are decoded for the revision of the cache, see [`Cache::with_revision`].
     fn fetch_from_index<D>(cache: &Cache, index_id: u8) -> crate::Result<HashMap<u16, D>>
     where
         D: Definition,
     {
-        let buffer = cache.read(REFERENCE_TABLE_ID, index_id as u32)?.decode()?;
+        let buffer = cache.decode(cache.read(REFERENCE_TABLE_ID, index_id as u32)?)?;
         let archives = IndexMetadata::from_buffer(buffer)?;
         let mut definitions = HashMap::new();
         for archive in &archives {
-            let buffer = cache.read(index_id, archive.id)?.decode()?;
+            let buffer = cache.read(index_id, archive.id)?;
+            if buffer.is_empty() {
+                continue;
+            }
+
+            let buffer = cache.decode(buffer)?;
+            if buffer.is_empty() {
+                continue;
+            }
 
-            definitions.insert(archive.id as u16, D::new(archive.id as u16, &buffer)?);
+            let id = u16::try_from(ArchiveId(archive.id))?;
+            let (definition, _) = D::new_with_options(id, &buffer, cache.decode_options)?;
+            definitions.insert(id, definition);
         }
 
         Ok(definitions)
@@ -57,9 +280,15 @@ pub trait FetchDefinition: Definition {
     ///
     /// Note: every archive contains multiple definitions. (N:1)
     ///
+    /// An archive or archive file with a length of zero is skipped instead of decoded.
+    ///
     /// # Errors
     ///
     /// Can return multiple errors: if reading, decoding or parsing definition buffers fail.
+    /// Archive ids that don't fit in a `u16` return `ReadError::ArchiveIdOutOfRange`.
+    /// Unknown opcodes are handled with the policy of the cache, see
+    /// [`Cache::with_unknown_opcode_policy`], and opcodes that changed between revisions
+    /// are decoded for the revision of the cache, see [`Cache::with_revision`].
     ///
     /// # Examples
     ///
@@ -89,19 +318,16 @@ pub trait FetchDefinition: Definition {
     where
         D: Definition,
     {
-        let buffer = cache.read(REFERENCE_TABLE_ID, index_id as u32)?.decode()?;
-        let archives = IndexMetadata::from_buffer(buffer)?;
-        let entry_count = archives[archive_id as usize - 1].entry_count;
-        let buffer = cache.read(index_id, archive_id)?.decode()?;
-
-        let archive_group = ArchiveFileGroup::from_buffer(&buffer, entry_count);
-
         let mut definitions = HashMap::new();
-        for archive_file in archive_group {
-            definitions.insert(
-                archive_file.id as u16,
-                D::new(archive_file.id as u16, &archive_file.data)?,
-            );
+        for archive_file in archive_files(cache, index_id, archive_id)? {
+            if archive_file.data.is_empty() {
+                continue;
+            }
+
+            let id = u16::try_from(ArchiveId(archive_file.id))?;
+            let (definition, _) =
+                D::new_with_options(id, &archive_file.data, cache.decode_options)?;
+            definitions.insert(id, definition);
         }
 
         Ok(definitions)
@@ -109,3 +335,233 @@ pub trait FetchDefinition: Definition {
 }
 
 impl<D: Definition> FetchDefinition for D {}
+
+/// The payload layout of opcodes that changed between revisions.
+///
+/// Caches don't store their revision, the layout follows [`LoaderOptions::revision`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum Layout {
+    Current,
+    Legacy,
+}
+
+impl Layout {
+    /// The layout `D` is stored in by caches of the given revision.
+    fn of<D: OpcodeDefinition>(revision: Option<u16>) -> Self {
+        match (revision, D::CURRENT_SINCE) {
+            (Some(revision), Some(since)) if revision < since => Self::Legacy,
+            _ => Self::Current,
+        }
+    }
+}
+
+/// A definition stored as a list of opcodes with their payloads, terminated by opcode 0.
+///
+/// These definitions implement [`Definition`] through `impl_opcode_definition!`, so every
+/// one of them handles layouts and unknown opcodes the same way.
+pub(crate) trait OpcodeDefinition: Sized {
+    /// The kind of definition, used in panic messages.
+    const NAME: &'static str;
+    /// The first revision that stores every opcode in [`Layout::Current`], `None` if no
+    /// opcode ever changed its payload.
+    const CURRENT_SINCE: Option<u16> = None;
+
+    /// The definition before any opcode is decoded.
+    fn with_defaults(id: u16) -> Self;
+
+    /// Decodes opcodes until the terminating opcode 0, or returns the first unknown opcode.
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        layout: Layout,
+    ) -> io::Result<Option<u8>>;
+
+    /// Fills in the fields that are derived from the decoded opcodes.
+    fn finish(&mut self) {}
+}
+
+/// A definition that is being decoded, keeps the decoded fields when decoding fails.
+pub(crate) struct OpcodeDecoder<'a, D> {
+    pub(crate) definition: D,
+    pub(crate) reader: Cursor<&'a [u8]>,
+    pub(crate) warnings: Vec<DecodeWarning>,
+}
+
+impl<'a, D: OpcodeDefinition> OpcodeDecoder<'a, D> {
+    fn new(id: u16, buffer: &'a [u8]) -> Self {
+        Self {
+            definition: D::with_defaults(id),
+            reader: Cursor::new(buffer),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Decodes a buffer in the layout of `options.revision`, handling unknown opcodes
+    /// according to the policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReadError::UnknownOpcode` for unknown opcodes under
+    /// `UnknownOpcodePolicy::Error`, or an I/O error when the buffer is cut short.
+    pub(crate) fn decode(id: u16, buffer: &'a [u8], options: LoaderOptions) -> io::Result<Self> {
+        let mut decoder = Self::new(id, buffer);
+        decoder.run(Layout::of::<D>(options.revision), options.on_unknown_opcode)?;
+        decoder.definition.finish();
+
+        Ok(decoder)
+    }
+
+    /// Decodes a buffer, keeping the decoded fields when decoding fails.
+    ///
+    /// Unknown opcodes are handled with `UnknownOpcodePolicy::Warn` and errors are
+    /// reported as a [`DecodeWarning::Stopped`].
+    pub(crate) fn decode_lenient(id: u16, buffer: &'a [u8]) -> Self {
+        let mut decoder = Self::new(id, buffer);
+        if let Err(error) = decoder.run(Layout::Current, UnknownOpcodePolicy::Warn) {
+            decoder
+                .warnings
+                .push(DecodeWarning::Stopped(error.to_string()));
+        }
+        decoder.definition.finish();
+
+        decoder
+    }
+
+    fn run(&mut self, layout: Layout, policy: UnknownOpcodePolicy) -> io::Result<()> {
+        while let Some(opcode) = self.definition.decode_opcodes(&mut self.reader, layout)? {
+            let offset = self.reader.position() as usize - 1;
+            match policy {
+                UnknownOpcodePolicy::Panic => {
+                    panic!("unknown {} opcode {opcode} at offset {offset}", D::NAME)
+                }
+                UnknownOpcodePolicy::Error => {
+                    return Err(crate::error::ReadError::UnknownOpcode { opcode, offset }.into())
+                }
+                UnknownOpcodePolicy::Skip(len) => self.reader.skip(len)?,
+                UnknownOpcodePolicy::Warn => {
+                    self.warnings
+                        .push(DecodeWarning::UnknownOpcode { opcode, offset });
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Implements [`Definition`] for definitions that implement [`OpcodeDefinition`].
+macro_rules! impl_opcode_definition {
+    ($($def:ty),+ $(,)?) => {$(
+        impl Definition for $def {
+            fn new(id: u16, buffer: &[u8]) -> crate::Result<Self> {
+                let decoder = OpcodeDecoder::decode(id, buffer, LoaderOptions::default())?;
+
+                Ok(decoder.definition)
+            }
+
+            fn new_strict(id: u16, buffer: &[u8]) -> crate::Result<Self> {
+                let decoder = OpcodeDecoder::decode(id, buffer, LoaderOptions::default())?;
+                util::ensure_consumed(&decoder.reader)?;
+
+                Ok(decoder.definition)
+            }
+
+            fn new_lenient(id: u16, buffer: &[u8]) -> (Self, Vec<DecodeWarning>) {
+                let decoder = OpcodeDecoder::decode_lenient(id, buffer);
+
+                (decoder.definition, decoder.warnings)
+            }
+
+            fn new_with_policy(
+                id: u16,
+                buffer: &[u8],
+                policy: UnknownOpcodePolicy,
+            ) -> crate::Result<(Self, Vec<DecodeWarning>)> {
+                let options = LoaderOptions {
+                    on_unknown_opcode: policy,
+                    ..LoaderOptions::default()
+                };
+
+                Self::new_with_options(id, buffer, options)
+            }
+
+            fn new_with_options(
+                id: u16,
+                buffer: &[u8],
+                options: LoaderOptions,
+            ) -> crate::Result<(Self, Vec<DecodeWarning>)> {
+                let decoder = OpcodeDecoder::decode(id, buffer, options)?;
+
+                Ok((decoder.definition, decoder.warnings))
+            }
+        }
+    )+};
+}
+
+impl_opcode_definition!(
+    AreaDefinition,
+    EnumDefinition,
+    HitsplatDefinition,
+    IdentikitDefinition,
+    ItemDefinition,
+    NpcDefinition,
+    ObjectDefinition,
+    OverlayDefinition,
+    ParamDefinition,
+    SequenceDefinition,
+    SpotAnimDefinition,
+    VarbitDefinition,
+);
+
+/// Reads and splits an archive into its files.
+///
+/// An archive with a length of zero has no files.
+pub(crate) fn archive_files(
+    cache: &Cache,
+    index_id: u8,
+    archive_id: u32,
+) -> crate::Result<Vec<ArchiveFileData>> {
+    let buffer = cache.decode(cache.read(REFERENCE_TABLE_ID, index_id as u32)?)?;
+    let archives = IndexMetadata::from_buffer(buffer)?;
+    let entry_count = archives
+        .iter()
+        .find(|archive| archive.id == archive_id)
+        .ok_or(RuneFsError::Read(ReadError::ArchiveNotFound {
+            idx: index_id,
+            arc: archive_id,
+        }))?
+        .entry_count;
+
+    let buffer = cache.read(index_id, archive_id)?;
+    if buffer.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let buffer = cache.decode(buffer)?;
+    if buffer.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(merge_chunks(ArchiveFileGroup::from_buffer(
+        &buffer,
+        entry_count,
+    )))
+}
+
+/// Joins the chunks of every archive file.
+///
+/// Large archives store their files in several chunks, the group lists every chunk of
+/// every file with the file's entry index as its id.
+pub(crate) fn merge_chunks(group: ArchiveFileGroup) -> Vec<ArchiveFileData> {
+    let mut files: Vec<ArchiveFileData> = Vec::new();
+    for chunk in group {
+        // The first chunk lists every entry in order, later chunks are appended.
+        match files.get_mut(chunk.id as usize) {
+            Some(file) => file.data.extend_from_slice(&chunk.data),
+            None => files.push(chunk),
+        }
+    }
+
+    files
+}
@@ -0,0 +1,105 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Layout, OpcodeDefinition};
+use crate::{extension::ReadExt, util::read_optional_smart};
+
+/// Contains all the information about a certain map area fetched from the cache through
+/// the [AreaLoader](../../loader/osrs/struct.AreaLoader.html).
+///
+/// Areas are the icons and labels drawn on the world map and minimap, objects refer
+/// to them through their `map_area_id`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct AreaDefinition {
+    pub id: u16,
+    pub sprite_id: Option<u32>,
+    pub hover_sprite_id: Option<u32>,
+    pub name: String,
+    pub text_color: u32,
+    pub text_size: u8,
+    pub options: [String; 5],
+    /// The corners of the outline of the area as offsets in tiles.
+    pub polygon: Vec<(i16, i16)>,
+    pub menu_target_name: String,
+    pub category: u16,
+}
+
+impl OpcodeDefinition for AreaDefinition {
+    const NAME: &'static str = "area";
+
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        _layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader)
+    }
+}
+
+fn decode_opcodes(
+    area_def: &mut AreaDefinition,
+    reader: &mut Cursor<&[u8]>,
+) -> io::Result<Option<u8>> {
+    loop {
+        let opcode = reader.read_u8()?;
+
+        match opcode {
+            0 => break,
+            1 => area_def.sprite_id = read_optional_smart(reader)?,
+            2 => area_def.hover_sprite_id = read_optional_smart(reader)?,
+            3 => area_def.name = reader.read_string()?,
+            4 => area_def.text_color = reader.read_u24()?,
+            5 => {
+                reader.read_u24()?;
+            }
+            6 => area_def.text_size = reader.read_u8()?,
+            7 | 8 | 28..=30 => {
+                reader.read_u8()?;
+            }
+            10..=14 => {
+                area_def.options[opcode as usize - 10] = reader.read_string()?;
+            }
+            15 => {
+                let len = reader.read_u8()?;
+                for _ in 0..len {
+                    area_def
+                        .polygon
+                        .push((reader.read_i16()?, reader.read_i16()?));
+                }
+                reader.read_u32()?;
+                let len = reader.read_u8()?;
+                reader.read_bytes(len as usize * 4)?;
+                // One byte per corner of the polygon.
+                reader.read_bytes(area_def.polygon.len())?;
+            }
+            16 => {}
+            17 => area_def.menu_target_name = reader.read_string()?,
+            18 | 25 => {
+                read_optional_smart(reader)?;
+            }
+            19 => area_def.category = reader.read_u16()?,
+            21 | 22 => {
+                reader.read_u32()?;
+            }
+            23 => {
+                reader.read_u24()?;
+            }
+            24 => {
+                reader.read_u32()?;
+            }
+            opcode => return Ok(Some(opcode)),
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,119 @@
+use std::{collections::HashMap, io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Layout, OpcodeDefinition};
+use crate::extension::ReadExt;
+
+/// The enum that maps music track ids to their names in the caches this crate is tested
+/// against.
+///
+/// Enum ids aren't fixed between revisions, check that it holds the track names of the
+/// cache at hand before passing it to
+/// [`EnumLoader::track_name`](crate::loader::osrs::EnumLoader::track_name).
+pub const MUSIC_TRACK_NAMES_ENUM: u16 = 812;
+
+/// Contains all the information about a certain enum fetched from the cache through
+/// the [EnumLoader](../../loader/osrs/struct.EnumLoader.html).
+///
+/// Enums are lookup tables used by client scripts, e.g. to map music track ids to
+/// their names. Depending on the `value_type` the values are either strings or integers.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct EnumDefinition {
+    pub id: u16,
+    /// The script type character of the keys, e.g. `i` for integers.
+    pub key_type: u8,
+    /// The script type character of the values, `s` for strings.
+    pub value_type: u8,
+    pub default_string: String,
+    pub default_int: i32,
+    pub string_values: HashMap<i32, String>,
+    pub int_values: HashMap<i32, i32>,
+}
+
+impl Default for EnumDefinition {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            id: 0,
+            key_type: 0,
+            value_type: 0,
+            default_string: String::from("null"),
+            default_int: 0,
+            string_values: HashMap::new(),
+            int_values: HashMap::new(),
+        }
+    }
+}
+
+impl EnumDefinition {
+    /// The string value for the given key, or the default string if the key is missing.
+    pub fn string_value(&self, key: i32) -> &str {
+        self.string_values
+            .get(&key)
+            .map_or(&self.default_string, |value| value)
+    }
+
+    /// The integer value for the given key, or the default integer if the key is missing.
+    pub fn int_value(&self, key: i32) -> i32 {
+        self.int_values
+            .get(&key)
+            .copied()
+            .unwrap_or(self.default_int)
+    }
+}
+
+impl OpcodeDefinition for EnumDefinition {
+    const NAME: &'static str = "enum";
+
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        _layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader)
+    }
+}
+
+fn decode_opcodes(
+    enum_def: &mut EnumDefinition,
+    reader: &mut Cursor<&[u8]>,
+) -> io::Result<Option<u8>> {
+    loop {
+        let opcode = reader.read_u8()?;
+
+        match opcode {
+            0 => break,
+            1 => enum_def.key_type = reader.read_u8()?,
+            2 => enum_def.value_type = reader.read_u8()?,
+            3 => enum_def.default_string = reader.read_string()?,
+            4 => enum_def.default_int = reader.read_i32()?,
+            5 => {
+                let len = reader.read_u16()?;
+                for _ in 0..len {
+                    let key = reader.read_i32()?;
+                    enum_def.string_values.insert(key, reader.read_string()?);
+                }
+            }
+            6 => {
+                let len = reader.read_u16()?;
+                for _ in 0..len {
+                    let key = reader.read_i32()?;
+                    enum_def.int_values.insert(key, reader.read_i32()?);
+                }
+            }
+            opcode => return Ok(Some(opcode)),
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,125 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{SkeletonDefinition, TRANSFORM_ORIGIN, TRANSFORM_SCALE};
+use crate::extension::ReadExt;
+
+/// Contains a single animation frame fetched from the cache through
+/// the [FrameLoader](../../loader/osrs/struct.FrameLoader.html).
+///
+/// A frame only stores the transforms that change something, each referencing a slot
+/// of its skeleton. Frames are identified like the frame ids of a
+/// [`SequenceDefinition`](super::SequenceDefinition), the frame archive in the upper
+/// 16 bits and the file in the lower 16 bits.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct FrameDefinition {
+    pub id: u32,
+    pub skeleton_id: u16,
+    pub transforms: Vec<FrameTransform>,
+}
+
+/// A transform of one skeleton slot, see [`SkeletonDefinition`] for how it is applied.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct FrameTransform {
+    /// The slot in the skeleton this transform applies to.
+    pub slot: u16,
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+}
+
+impl FrameDefinition {
+    /// Decodes a frame with the skeleton it was made for.
+    ///
+    /// Like the client, an origin transform is inserted before every transform that
+    /// isn't preceded by one, resetting the origin to zero. Scale transforms default
+    /// to 128 on the axes that aren't stored, all other transforms default to 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the buffer is truncated.
+    pub fn new(id: u32, buffer: &[u8], skeleton: &SkeletonDefinition) -> crate::Result<Self> {
+        let mut reader = Cursor::new(buffer);
+        let frame_def = decode_buffer(id, &mut reader, skeleton)?;
+
+        Ok(frame_def)
+    }
+
+    /// Reads the id of the skeleton a frame buffer was made for.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the buffer is shorter than 2 bytes.
+    pub fn skeleton_id(mut buffer: &[u8]) -> crate::Result<u16> {
+        Ok(buffer.read_u16()?)
+    }
+}
+
+fn decode_buffer(
+    id: u32,
+    reader: &mut Cursor<&[u8]>,
+    skeleton: &SkeletonDefinition,
+) -> io::Result<FrameDefinition> {
+    let skeleton_id = reader.read_u16()?;
+    let len = reader.read_u8()? as usize;
+    let flags = reader.read_bytes(len)?;
+    let slot_type = |slot: usize| {
+        skeleton
+            .types
+            .get(slot)
+            .copied()
+            .unwrap_or(TRANSFORM_ORIGIN)
+    };
+
+    let mut transforms = Vec::new();
+    let mut last_slot = None;
+    for (slot, &flag) in flags.iter().enumerate() {
+        if flag == 0 {
+            continue;
+        }
+
+        if slot_type(slot) != TRANSFORM_ORIGIN {
+            let first = last_slot.map_or(0, |last| last + 1);
+            if let Some(origin) = (first..slot)
+                .rev()
+                .find(|&i| slot_type(i) == TRANSFORM_ORIGIN)
+            {
+                transforms.push(FrameTransform {
+                    slot: origin as u16,
+                    ..FrameTransform::default()
+                });
+            }
+        }
+
+        let default = if slot_type(slot) == TRANSFORM_SCALE {
+            128
+        } else {
+            0
+        };
+        let mut read_axis = |bit: u8| -> io::Result<i16> {
+            if flag & bit != 0 {
+                reader.read_smart_i16()
+            } else {
+                Ok(default)
+            }
+        };
+
+        transforms.push(FrameTransform {
+            slot: slot as u16,
+            x: read_axis(1)?,
+            y: read_axis(2)?,
+            z: read_axis(4)?,
+        });
+        last_slot = Some(slot);
+    }
+
+    Ok(FrameDefinition {
+        id,
+        skeleton_id,
+        transforms,
+    })
+}
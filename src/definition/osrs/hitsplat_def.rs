@@ -0,0 +1,148 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Layout, OpcodeDefinition};
+use crate::{extension::ReadExt, util::read_optional_smart};
+
+/// Contains all the information about a certain hitsplat fetched from the cache through
+/// the [HitsplatLoader](../../loader/osrs/struct.HitsplatLoader.html).
+///
+/// The background of a hitsplat is drawn as the left sprite, the middle sprite repeated
+/// to fit the text and the right sprite. All sprite ids are archives of the sprite index.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct HitsplatDefinition {
+    pub id: u16,
+    pub font_id: Option<u32>,
+    pub text_color: u32,
+    pub icon_sprite_id: Option<u32>,
+    pub left_sprite_id: Option<u32>,
+    pub middle_sprite_id: Option<u32>,
+    pub right_sprite_id: Option<u32>,
+    pub scroll_offset_x: i16,
+    pub scroll_offset_y: i16,
+    pub text_offset_y: i16,
+    /// The text of the hitsplat where `%1` is replaced by the damage.
+    pub format: String,
+    pub display_cycles: u16,
+    pub fade_start_cycle: Option<u16>,
+    pub use_damage: Option<u8>,
+    pub configs: Vec<u16>,
+    pub varbit_id: Option<u16>,
+    pub varp_index: Option<u16>,
+}
+
+impl Default for HitsplatDefinition {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            id: 0,
+            font_id: None,
+            text_color: 0xFFFFFF,
+            icon_sprite_id: None,
+            left_sprite_id: None,
+            middle_sprite_id: None,
+            right_sprite_id: None,
+            scroll_offset_x: 0,
+            scroll_offset_y: 0,
+            text_offset_y: 0,
+            format: String::new(),
+            display_cycles: 70,
+            fade_start_cycle: None,
+            use_damage: None,
+            configs: Vec::new(),
+            varbit_id: None,
+            varp_index: None,
+        }
+    }
+}
+
+impl HitsplatDefinition {
+    /// The text shown on the hitsplat for the given damage.
+    pub fn format_damage(&self, damage: i32) -> String {
+        self.format.replace("%1", &damage.to_string())
+    }
+}
+
+impl OpcodeDefinition for HitsplatDefinition {
+    const NAME: &'static str = "hitsplat";
+
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        _layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader)
+    }
+}
+
+fn decode_opcodes(
+    hitsplat_def: &mut HitsplatDefinition,
+    reader: &mut Cursor<&[u8]>,
+) -> io::Result<Option<u8>> {
+    loop {
+        let opcode = reader.read_u8()?;
+
+        match opcode {
+            0 => break,
+            1 => hitsplat_def.font_id = read_optional_smart(reader)?,
+            2 => hitsplat_def.text_color = reader.read_u24()?,
+            3 => hitsplat_def.icon_sprite_id = read_optional_smart(reader)?,
+            4 => hitsplat_def.left_sprite_id = read_optional_smart(reader)?,
+            5 => hitsplat_def.middle_sprite_id = read_optional_smart(reader)?,
+            6 => hitsplat_def.right_sprite_id = read_optional_smart(reader)?,
+            7 => hitsplat_def.scroll_offset_x = reader.read_i16()?,
+            8 => {
+                // The string is prefixed with a null byte.
+                reader.read_u8()?;
+                hitsplat_def.format = reader.read_string()?;
+            }
+            9 => hitsplat_def.display_cycles = reader.read_u16()?,
+            10 => hitsplat_def.scroll_offset_y = reader.read_i16()?,
+            11 => hitsplat_def.fade_start_cycle = Some(0),
+            12 => hitsplat_def.use_damage = Some(reader.read_u8()?),
+            13 => hitsplat_def.text_offset_y = reader.read_i16()?,
+            14 => hitsplat_def.fade_start_cycle = Some(reader.read_u16()?),
+            17 | 18 => {
+                let varbit_id = reader.read_u16()?;
+                hitsplat_def.varbit_id = if varbit_id == u16::MAX {
+                    None
+                } else {
+                    Some(varbit_id)
+                };
+
+                let varp_index = reader.read_u16()?;
+                hitsplat_def.varp_index = if varp_index == u16::MAX {
+                    None
+                } else {
+                    Some(varp_index)
+                };
+
+                let default = if opcode == 18 {
+                    reader.read_u16()?
+                } else {
+                    u16::MAX
+                };
+
+                hitsplat_def.configs = Vec::new();
+                let len = reader.read_u8()?;
+                for _ in 0..=len {
+                    hitsplat_def.configs.push(reader.read_u16()?);
+                }
+                hitsplat_def.configs.push(default);
+            }
+            opcode => return Ok(Some(opcode)),
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,109 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Layout, OpcodeDefinition};
+use crate::extension::ReadExt;
+
+/// The number of body parts a character is made of, per gender.
+pub const BODY_PART_COUNT: usize = 7;
+
+/// Contains all the information about a certain identikit fetched from the cache through
+/// the [IdentikitLoader](../../loader/osrs/struct.IdentikitLoader.html).
+///
+/// An identikit is one of the selectable looks for a body part of a character.
+/// Body parts `0..7` belong to male characters and `7..14` to female characters,
+/// in the order head, jaw, torso, arms, hands, legs and feet.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct IdentikitDefinition {
+    pub id: u16,
+    pub body_part: Option<u8>,
+    pub models: Vec<u16>,
+    pub head_models: [Option<u16>; 5],
+    pub non_selectable: bool,
+    pub recolor_find: Vec<u16>,
+    pub recolor_replace: Vec<u16>,
+    pub retexture_find: Vec<u16>,
+    pub retexture_replace: Vec<u16>,
+}
+
+impl IdentikitDefinition {
+    /// Whether the kit can be picked in the character designer.
+    #[inline]
+    pub const fn selectable(&self) -> bool {
+        !self.non_selectable
+    }
+
+    /// Whether the kit is meant for female characters.
+    #[inline]
+    pub fn female(&self) -> bool {
+        matches!(self.body_part, Some(part) if part as usize >= BODY_PART_COUNT)
+    }
+}
+
+impl OpcodeDefinition for IdentikitDefinition {
+    const NAME: &'static str = "identikit";
+
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        _layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader)
+    }
+}
+
+fn decode_opcodes(
+    identikit_def: &mut IdentikitDefinition,
+    reader: &mut Cursor<&[u8]>,
+) -> io::Result<Option<u8>> {
+    loop {
+        let opcode = reader.read_u8()?;
+
+        match opcode {
+            0 => break,
+            1 => {
+                identikit_def.body_part = Some(reader.read_u8()?);
+            }
+            2 => {
+                let len = reader.read_u8()?;
+                for _ in 0..len {
+                    identikit_def.models.push(reader.read_u16()?);
+                }
+            }
+            3 => identikit_def.non_selectable = true,
+            40 => {
+                let len = reader.read_u8()?;
+                for _ in 0..len {
+                    identikit_def.recolor_find.push(reader.read_u16()?);
+                    identikit_def.recolor_replace.push(reader.read_u16()?);
+                }
+            }
+            41 => {
+                let len = reader.read_u8()?;
+                for _ in 0..len {
+                    identikit_def.retexture_find.push(reader.read_u16()?);
+                    identikit_def.retexture_replace.push(reader.read_u16()?);
+                }
+            }
+            60..=69 => {
+                let model = reader.read_u16()?;
+                if let Some(slot) = identikit_def.head_models.get_mut(opcode as usize - 60) {
+                    *slot = Some(model);
+                }
+            }
+            opcode => return Ok(Some(opcode)),
+        }
+    }
+
+    Ok(None)
+}
@@ -1,10 +1,14 @@
-use std::{collections::HashMap, io, io::BufReader};
+use std::{collections::HashMap, io, io::Cursor, sync::OnceLock};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use super::Definition;
-use crate::{extension::ReadExt, util};
+use super::{Definition, Layout, OpcodeDefinition, ParamValue};
+use crate::{
+    extension::{PayloadSize, ReadExt},
+    loader::osrs::ParamLoader,
+    util,
+};
 
 /// Contains all the information about a certain item fetched from the cache through
 /// the [ItemLoader](../../loader/osrs/struct.ItemLoader.html).
@@ -18,6 +22,8 @@ pub struct ItemDefinition {
     pub members_only: bool,
     pub options: [String; 5],
     pub interface_options: [String; 5],
+    /// The string of opcode 9, what the client uses it for isn't known yet.
+    pub inventory_option: Option<String>,
     pub tradable: bool,
     pub noted_id: Option<u16>,
     pub noted_template: Option<u16>,
@@ -25,17 +31,21 @@ pub struct ItemDefinition {
     pub stack_count: Option<[u16; 10]>,
     pub team: u8,
     pub bought_link: Option<u16>,
-    pub bought_tempalte: Option<u16>,
+    #[cfg_attr(feature = "serde", serde(alias = "bought_tempalte"))]
+    pub bought_template: Option<u16>,
     pub shift_click_drop_index: Option<u8>,
     pub params: HashMap<u32, String>,
     pub inventory_model_data: InventoryModelData,
     pub character_model_data: CharacterModelData,
-    pub weight: u16,
+    pub weight: i16,
     pub category: u16,
     pub placeholder_id: Option<u16>,
     pub placeholder_template_id: Option<u16>,
 }
 
+/// How an item is drawn in the inventory.
+///
+/// When the item doesn't set them `zoom2d` is 2000 and the `resize_*` fields are 128.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct InventoryModelData {
@@ -74,45 +84,196 @@ pub struct CharacterModelData {
     pub female_head_model2: Option<u16>,
 }
 
-impl Definition for ItemDefinition {
-    fn new(id: u16, buffer: &[u8]) -> crate::Result<Self> {
-        let mut reader = BufReader::new(buffer);
-        let item_def = decode_buffer(id, &mut reader)?;
+impl ItemDefinition {
+    /// The amount of coins high alchemy yields, 60% of the item's cost rounded down.
+    ///
+    /// Untradable items and items without a cost yield nothing.
+    pub fn high_alch(&self) -> i32 {
+        self.alch_value(60)
+    }
+
+    /// The amount of coins low alchemy yields, 40% of the item's cost rounded down.
+    ///
+    /// Untradable items and items without a cost yield nothing.
+    pub fn low_alch(&self) -> i32 {
+        self.alch_value(40)
+    }
+
+    /// The store value of this item, read from the integer param with the given key.
+    ///
+    /// OSRS caches have no fixed param for store values, so the key depends on the server.
+    /// Falls back to the cost when the param is missing or not an integer, items without a
+    /// cost have no store value.
+    pub fn store_value(&self, param: u32) -> Option<i32> {
+        self.params
+            .get(&param)
+            .and_then(|value| value.parse().ok())
+            .or((self.cost > 0).then_some(self.cost))
+    }
+
+    /// The value of the param with the given key, typed by its param config.
+    ///
+    /// Falls back to the default of the param config when this item doesn't set the param.
+    /// Returns `None` if the param config doesn't exist.
+    pub fn param_or_default(&self, key: u32, param_loader: &ParamLoader) -> Option<ParamValue> {
+        let param = param_loader.load(u16::try_from(key).ok()?)?;
+
+        Some(
+            self.params
+                .get(&key)
+                .map_or_else(|| param.default_value(), |raw| param.value(raw)),
+        )
+    }
+
+    /// The weight of this item in kilograms.
+    ///
+    /// Weights are stored in grams and can be negative, e.g. for weight reducing capes.
+    pub fn weight_kg(&self) -> f32 {
+        f32::from(self.weight) / 1000.0
+    }
+
+    /// The ground options that are set, in slot order.
+    pub fn options_present(&self) -> Vec<&str> {
+        util::present(&self.options)
+    }
+
+    /// Compares the fields that affect gameplay, ignoring cosmetic ones like model data.
+    ///
+    /// The compared fields are the name, cost, tradability, stackability, membership,
+    /// the ground and inventory options and the params.
+    pub fn gameplay_eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.cost == other.cost
+            && self.tradable == other.tradable
+            && self.stackable == other.stackable
+            && self.members_only == other.members_only
+            && self.options == other.options
+            && self.interface_options == other.interface_options
+            && self.params == other.params
+    }
+
+    fn alch_value(&self, percentage: i64) -> i32 {
+        if !self.tradable || self.cost <= 0 {
+            return 0;
+        }
+
+        (i64::from(self.cost) * percentage / 100) as i32
+    }
+}
+
+impl InventoryModelData {
+    /// Pairs every color that is searched for with the color that replaces it.
+    pub fn recolor_pairs(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        debug_assert_eq!(self.color_find.len(), self.color_replace.len());
+
+        self.color_find
+            .iter()
+            .copied()
+            .zip(self.color_replace.iter().copied())
+    }
+}
+
+impl OpcodeDefinition for ItemDefinition {
+    const NAME: &'static str = "item";
 
-        Ok(item_def)
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            inventory_model_data: InventoryModelData {
+                resize_x: 128,
+                resize_y: 128,
+                resize_z: 128,
+                zoom2d: 2000,
+                ..InventoryModelData::default()
+            },
+            options: [
+                "".to_string(),
+                "".to_string(),
+                "Take".to_string(),
+                "".to_string(),
+                "".to_string(),
+            ],
+            interface_options: [
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+                "Drop".to_string(),
+            ],
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        _layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader)
+    }
+}
+
+/// The opcodes of an item buffer in the order they appear, used to build opcode histograms.
+///
+/// Opcodes are split with [`ReadExt::read_until_opcode_zero`] using the payload sizes of the
+/// decoder. Scanning stops at the first opcode that isn't known, which is still recorded.
+pub(crate) struct ItemOpcodes(pub(crate) Vec<u8>);
+
+impl Definition for ItemOpcodes {
+    fn new(_id: u16, mut buffer: &[u8]) -> crate::Result<Self> {
+        let opcodes = buffer.read_until_opcode_zero(payload_sizes())?;
+
+        Ok(Self(
+            opcodes.into_iter().map(|(opcode, _)| opcode).collect(),
+        ))
     }
 }
 
-fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefinition> {
-    let mut item_def = ItemDefinition {
-        id,
-        inventory_model_data: InventoryModelData {
-            resize_x: 128,
-            resize_y: 128,
-            resize_z: 128,
-            zoom2d: 2000,
-            ..InventoryModelData::default()
-        },
-        options: [
-            "".to_string(),
-            "".to_string(),
-            "Take".to_string(),
-            "".to_string(),
-            "".to_string(),
-        ],
-        interface_options: [
-            "".to_string(),
-            "".to_string(),
-            "".to_string(),
-            "".to_string(),
-            "Drop".to_string(),
-        ],
-        ..ItemDefinition::default()
-    };
+/// Opcodes whose payload is read and thrown away, with their length in bytes.
+const SKIPPED_OPCODES: &[(u8, usize)] = &[(13, 1), (14, 1), (27, 1)];
+
+/// The payload size of every opcode the decoder knows.
+fn payload_sizes() -> &'static HashMap<u8, PayloadSize> {
+    static SIZES: OnceLock<HashMap<u8, PayloadSize>> = OnceLock::new();
+
+    SIZES.get_or_init(|| {
+        let mut sizes: HashMap<_, _> = SKIPPED_OPCODES
+            .iter()
+            .map(|&(opcode, len)| (opcode, PayloadSize::Fixed(len)))
+            .collect();
+
+        for opcode in 1..=u8::MAX {
+            let size = match opcode {
+                11 | 16 | 65 => PayloadSize::Fixed(0),
+                42 | 113..=115 => PayloadSize::Fixed(1),
+                1 | 4..=8 | 24 | 26 | 75 | 78 | 79 => PayloadSize::Fixed(2),
+                90..=95 | 97 | 98 | 110..=112 | 139 | 140 | 148 | 149 => PayloadSize::Fixed(2),
+                23 | 25 => PayloadSize::Fixed(3),
+                12 | 100..=109 => PayloadSize::Fixed(4),
+                2 | 9 | 30..=39 => PayloadSize::String,
+                40 | 41 => PayloadSize::Counted(4),
+                249 => PayloadSize::Params,
+                _ => continue,
+            };
+            sizes.insert(opcode, size);
+        }
+
+        sizes
+    })
+}
 
+/// Decodes opcodes until the terminating opcode 0, or returns the first unknown opcode.
+fn decode_opcodes(
+    item_def: &mut ItemDefinition,
+    reader: &mut Cursor<&[u8]>,
+) -> io::Result<Option<u8>> {
     loop {
         let opcode = reader.read_u8()?;
 
+        if util::skip_opcode(reader, SKIPPED_OPCODES, opcode)? {
+            continue;
+        }
+
         match opcode {
             0 => break,
             1 => {
@@ -137,7 +298,7 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
                 item_def.inventory_model_data.y_offset2d = reader.read_u16()?;
             }
             9 => {
-                let _ = reader.read_string()?;
+                item_def.inventory_option = Some(reader.read_string()?);
             }
             11 => {
                 item_def.stackable = true;
@@ -145,9 +306,6 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
             12 => {
                 item_def.cost = reader.read_i32()?;
             }
-            13..=14 => {
-                let _ = reader.read_u8()?;
-            }
             16 => item_def.members_only = true,
             23 => {
                 item_def.character_model_data.male_model10 = Some(reader.read_u16()?);
@@ -163,9 +321,6 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
             26 => {
                 item_def.character_model_data.female_model1 = Some(reader.read_u16()?);
             }
-            27 => {
-                let _ = reader.read_u8()?;
-            }
             30..=34 => {
                 item_def.options[opcode as usize - 30] = reader.read_string()?;
             }
@@ -209,7 +364,7 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
                 item_def.tradable = true;
             }
             75 => {
-                item_def.weight = reader.read_u16()?;
+                item_def.weight = reader.read_i16()?;
             }
             78 => {
                 item_def.character_model_data.male_model12 = Some(reader.read_u16()?);
@@ -243,20 +398,9 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
                 item_def.stackable = true;
             }
             100..=109 => {
-                item_def.stack_ids = Some([0; 10]);
-                item_def.stack_count = Some([0; 10]);
-                match item_def.stack_ids {
-                    Some(mut stack_ids) => {
-                        stack_ids[opcode as usize - 100] = reader.read_u16()?;
-                    }
-                    _ => unreachable!(),
-                }
-                match item_def.stack_count {
-                    Some(mut stack_count) => {
-                        stack_count[opcode as usize - 100] = reader.read_u16()?;
-                    }
-                    _ => unreachable!(),
-                }
+                let index = opcode as usize - 100;
+                item_def.stack_ids.get_or_insert([0; 10])[index] = reader.read_u16()?;
+                item_def.stack_count.get_or_insert([0; 10])[index] = reader.read_u16()?;
             }
             110 => {
                 item_def.inventory_model_data.resize_x = reader.read_u16()?;
@@ -280,7 +424,7 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
                 item_def.bought_link = Some(reader.read_u16()?);
             }
             140 => {
-                item_def.bought_tempalte = Some(reader.read_u16()?);
+                item_def.bought_template = Some(reader.read_u16()?);
             }
             148 => {
                 item_def.placeholder_id = Some(reader.read_u16()?);
@@ -291,9 +435,25 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
             249 => {
                 item_def.params = util::read_parameters(reader)?;
             }
-            _ => unreachable!(),
+            unknown => return Ok(Some(unknown)),
         }
     }
 
-    Ok(item_def)
+    Ok(None)
+}
+
+#[test]
+fn payload_sizes_cover_decoder() {
+    for opcode in 1..=u8::MAX {
+        // Zeros are an empty payload for every layout, the next zero ends the definition.
+        let buffer = [opcode, 0, 0, 0, 0, 0];
+        let mut item_def = ItemDefinition::with_defaults(0);
+        let unknown = decode_opcodes(&mut item_def, &mut Cursor::new(&buffer)).unwrap();
+
+        assert_eq!(
+            unknown.is_none(),
+            payload_sizes().contains_key(&opcode),
+            "opcode {opcode}"
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use std::{io, io::BufReader};
+use std::{io, io::Cursor};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -37,7 +37,7 @@ impl Definition for MapDefinition {
         let x = id >> 8;
         let y = id & 0xFF;
 
-        let mut reader = BufReader::new(buffer);
+        let mut reader = Cursor::new(buffer);
         let map_def = decode_buffer(x, y, &mut reader)?;
 
         Ok(map_def)
@@ -79,7 +79,7 @@ impl MapDefinition {
     }
 }
 
-fn decode_buffer(x: u16, y: u16, reader: &mut BufReader<&[u8]>) -> io::Result<MapDefinition> {
+fn decode_buffer(x: u16, y: u16, reader: &mut Cursor<&[u8]>) -> io::Result<MapDefinition> {
     let mut map_def = MapDefinition {
         region_x: x,
         region_y: y,
@@ -0,0 +1,356 @@
+use std::io;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::Definition;
+use crate::extension::ReadExt;
+
+/// Contains the geometry of a certain model fetched from the cache through
+/// the [ModelLoader](../../loader/osrs/struct.ModelLoader.html).
+///
+/// Every face is a triangle referencing three vertices by their index and has
+/// a single HSL color.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ModelDefinition {
+    pub id: u16,
+    pub vertices: Vec<(i32, i32, i32)>,
+    pub faces: Vec<[u16; 3]>,
+    pub face_colors: Vec<u16>,
+}
+
+impl ModelDefinition {
+    #[inline]
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    #[inline]
+    pub fn face_count(&self) -> usize {
+        self.faces.len()
+    }
+
+    /// Replaces every face color in `find` with the color at the same position in `replace`.
+    ///
+    /// Pairs are applied in order like the client does, so a face recolored by one pair
+    /// can be recolored again by a later pair. Used with the `color_find` and
+    /// `color_replace` of item, npc and object definitions.
+    pub fn apply_recolor(&mut self, find: &[u16], replace: &[u16]) {
+        debug_assert_eq!(find.len(), replace.len());
+
+        for (&find, &replace) in find.iter().zip(replace) {
+            for color in &mut self.face_colors {
+                if *color == find {
+                    *color = replace;
+                }
+            }
+        }
+    }
+
+    /// Exports the model as a Wavefront OBJ.
+    ///
+    /// The y-axis is flipped so the model stands upright. Every face uses the
+    /// material named after its color, these are defined by [`to_mtl`](Self::to_mtl)
+    /// which the OBJ references as `model_<id>.mtl`.
+    #[cfg(feature = "obj")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "obj")))]
+    pub fn to_obj(&self) -> String {
+        use std::fmt::Write;
+
+        let mut obj = format!("mtllib model_{}.mtl\no model_{}\n", self.id, self.id);
+        for (x, y, z) in &self.vertices {
+            let _ = writeln!(obj, "v {} {} {}", x, -y, z);
+        }
+
+        let mut material = None;
+        for (face, color) in self.faces.iter().zip(&self.face_colors) {
+            if material != Some(color) {
+                let _ = writeln!(obj, "usemtl hsl_{}", color);
+                material = Some(color);
+            }
+            // OBJ indices start at 1.
+            let [a, b, c] = face.map(|vertex| vertex as u32 + 1);
+            let _ = writeln!(obj, "f {} {} {}", a, b, c);
+        }
+
+        obj
+    }
+
+    /// Exports the face colors as a Wavefront MTL to go along with [`to_obj`](Self::to_obj).
+    #[cfg(feature = "obj")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "obj")))]
+    pub fn to_mtl(&self) -> String {
+        use std::fmt::Write;
+
+        let mut colors = self.face_colors.clone();
+        colors.sort_unstable();
+        colors.dedup();
+
+        let mut mtl = String::new();
+        for color in colors {
+            let (r, g, b) = hsl_to_rgb(color);
+            let _ = writeln!(mtl, "newmtl hsl_{}\nKd {:.4} {:.4} {:.4}", color, r, g, b);
+        }
+
+        mtl
+    }
+}
+
+/// Converts a packed 16-bit HSL color (6 bits hue, 3 bits saturation and 7 bits
+/// lightness) to RGB components between 0 and 1.
+#[cfg(feature = "obj")]
+fn hsl_to_rgb(hsl: u16) -> (f32, f32, f32) {
+    let hue = ((hsl >> 10) & 0x3F) as f32 / 64.0 + 0.0078125;
+    let saturation = ((hsl >> 7) & 0x07) as f32 / 8.0 + 0.0625;
+    let lightness = (hsl & 0x7F) as f32 / 128.0;
+
+    let q = if lightness < 0.5 {
+        lightness * (1.0 + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2.0 * lightness - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.0);
+        if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        }
+    };
+
+    (
+        channel(hue + 1.0 / 3.0),
+        channel(hue),
+        channel(hue - 1.0 / 3.0),
+    )
+}
+
+impl Definition for ModelDefinition {
+    fn new(id: u16, buffer: &[u8]) -> crate::Result<Self> {
+        let model_def = decode_buffer(id, buffer)?;
+
+        Ok(model_def)
+    }
+}
+
+/// Where the sections needed for the geometry start within the model buffer.
+struct Layout {
+    vertex_count: usize,
+    face_count: usize,
+    vertex_flags: usize,
+    face_types: usize,
+    face_indices: usize,
+    face_colors: usize,
+    vertex_x: usize,
+    vertex_y: usize,
+    vertex_z: usize,
+}
+
+impl Layout {
+    /// Models ending with `0xFFFF` store a 23 byte footer describing the sections.
+    fn new_format(buffer: &[u8]) -> io::Result<Self> {
+        let mut footer = section(buffer, buffer.len().saturating_sub(23))?;
+        let vertex_count = footer.read_u16()? as usize;
+        let face_count = footer.read_u16()? as usize;
+        let textured_face_count = footer.read_u8()? as usize;
+        let has_render_types = footer.read_u8()? == 1;
+        let has_priorities = footer.read_u8()? == 255;
+        let has_alphas = footer.read_u8()? == 1;
+        let has_face_skins = footer.read_u8()? == 1;
+        let has_textures = footer.read_u8()? == 1;
+        let has_vertex_skins = footer.read_u8()? == 1;
+        let vertex_x_len = footer.read_u16()? as usize;
+        let vertex_y_len = footer.read_u16()? as usize;
+        let vertex_z_len = footer.read_u16()? as usize;
+        let face_indices_len = footer.read_u16()? as usize;
+        let texture_coords_len = footer.read_u16()? as usize;
+
+        // The texture render types come first, followed by the vertex flags.
+        let vertex_flags = textured_face_count;
+        let mut pos = vertex_flags + vertex_count;
+        if has_render_types {
+            pos += face_count;
+        }
+        let face_types = pos;
+        pos += face_count;
+        if has_priorities {
+            pos += face_count;
+        }
+        if has_face_skins {
+            pos += face_count;
+        }
+        if has_vertex_skins {
+            pos += vertex_count;
+        }
+        if has_alphas {
+            pos += face_count;
+        }
+        let face_indices = pos;
+        pos += face_indices_len;
+        if has_textures {
+            pos += face_count * 2;
+        }
+        pos += texture_coords_len;
+        let face_colors = pos;
+        pos += face_count * 2;
+        let vertex_x = pos;
+        let vertex_y = vertex_x + vertex_x_len;
+        let vertex_z = vertex_y + vertex_y_len;
+        section(buffer, vertex_z + vertex_z_len)?;
+
+        Ok(Self {
+            vertex_count,
+            face_count,
+            vertex_flags,
+            face_types,
+            face_indices,
+            face_colors,
+            vertex_x,
+            vertex_y,
+            vertex_z,
+        })
+    }
+
+    /// Older models store an 18 byte footer describing the sections.
+    fn old_format(buffer: &[u8]) -> io::Result<Self> {
+        let mut footer = section(buffer, buffer.len().saturating_sub(18))?;
+        let vertex_count = footer.read_u16()? as usize;
+        let face_count = footer.read_u16()? as usize;
+        let textured_face_count = footer.read_u8()? as usize;
+        let has_textures = footer.read_u8()? == 1;
+        let has_priorities = footer.read_u8()? == 255;
+        let has_alphas = footer.read_u8()? == 1;
+        let has_face_skins = footer.read_u8()? == 1;
+        let has_vertex_skins = footer.read_u8()? == 1;
+        let vertex_x_len = footer.read_u16()? as usize;
+        let vertex_y_len = footer.read_u16()? as usize;
+        let vertex_z_len = footer.read_u16()? as usize;
+        let face_indices_len = footer.read_u16()? as usize;
+
+        let vertex_flags = 0;
+        let face_types = vertex_count;
+        let mut pos = face_types + face_count;
+        if has_priorities {
+            pos += face_count;
+        }
+        if has_face_skins {
+            pos += face_count;
+        }
+        if has_textures {
+            pos += face_count;
+        }
+        if has_vertex_skins {
+            pos += vertex_count;
+        }
+        if has_alphas {
+            pos += face_count;
+        }
+        let face_indices = pos;
+        pos += face_indices_len;
+        let face_colors = pos;
+        pos += face_count * 2 + textured_face_count * 6;
+        let vertex_x = pos;
+        let vertex_y = vertex_x + vertex_x_len;
+        let vertex_z = vertex_y + vertex_y_len;
+        section(buffer, vertex_z + vertex_z_len)?;
+
+        Ok(Self {
+            vertex_count,
+            face_count,
+            vertex_flags,
+            face_types,
+            face_indices,
+            face_colors,
+            vertex_x,
+            vertex_y,
+            vertex_z,
+        })
+    }
+}
+
+fn decode_buffer(id: u16, buffer: &[u8]) -> io::Result<ModelDefinition> {
+    let layout = if buffer.ends_with(&[0xFF, 0xFF]) {
+        Layout::new_format(buffer)?
+    } else {
+        Layout::old_format(buffer)?
+    };
+
+    // Vertices are stored as deltas from the previous vertex, the flags tell
+    // which of the axes changed.
+    let mut flags = section(buffer, layout.vertex_flags)?;
+    let mut x_reader = section(buffer, layout.vertex_x)?;
+    let mut y_reader = section(buffer, layout.vertex_y)?;
+    let mut z_reader = section(buffer, layout.vertex_z)?;
+    let mut vertices = Vec::with_capacity(layout.vertex_count);
+    let (mut x, mut y, mut z) = (0, 0, 0);
+    for _ in 0..layout.vertex_count {
+        let flag = flags.read_u8()?;
+        if flag & 1 != 0 {
+            x += x_reader.read_smart_i16()? as i32;
+        }
+        if flag & 2 != 0 {
+            y += y_reader.read_smart_i16()? as i32;
+        }
+        if flag & 4 != 0 {
+            z += z_reader.read_smart_i16()? as i32;
+        }
+        vertices.push((x, y, z));
+    }
+
+    let mut reader = section(buffer, layout.face_colors)?;
+    let mut face_colors = Vec::with_capacity(layout.face_count);
+    for _ in 0..layout.face_count {
+        face_colors.push(reader.read_u16()?);
+    }
+
+    // Faces are strips, the type tells how many indices are new and which
+    // ones are reused from the previous face.
+    let mut types = section(buffer, layout.face_types)?;
+    let mut reader = section(buffer, layout.face_indices)?;
+    let mut faces = Vec::with_capacity(layout.face_count);
+    let (mut a, mut b, mut c) = (0, 0, 0);
+    let mut last = 0;
+    for _ in 0..layout.face_count {
+        match types.read_u8()? {
+            1 => {
+                a = reader.read_smart_i16()? as i32 + last;
+                b = reader.read_smart_i16()? as i32 + a;
+                c = reader.read_smart_i16()? as i32 + b;
+            }
+            2 => {
+                b = c;
+                c = reader.read_smart_i16()? as i32 + last;
+            }
+            3 => {
+                a = c;
+                c = reader.read_smart_i16()? as i32 + last;
+            }
+            4 => {
+                std::mem::swap(&mut a, &mut b);
+                c = reader.read_smart_i16()? as i32 + last;
+            }
+            _ => {}
+        }
+        last = c;
+        faces.push([a as u16, b as u16, c as u16]);
+    }
+
+    Ok(ModelDefinition {
+        id,
+        vertices,
+        faces,
+        face_colors,
+    })
+}
+
+fn section(buffer: &[u8], offset: usize) -> io::Result<&[u8]> {
+    buffer
+        .get(offset..)
+        .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))
+}
@@ -1,9 +1,9 @@
-use std::{collections::HashMap, io, io::BufReader};
+use std::{collections::HashMap, io, io::Cursor};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use super::Definition;
+use super::{Layout, OpcodeDefinition};
 use crate::{extension::ReadExt, util};
 
 /// Contains all the information about a certain npc fetched from the cache through
@@ -45,10 +45,22 @@ pub struct NpcModelData {
     pub ambient: u8,
     pub contrast: u8,
     pub head_icon: Option<u16>,
+    /// Overhead icons indexed by their slot, `None` for slots that are not set.
+    pub head_icons: Vec<Option<NpcHeadIcon>>,
     pub rotate_speed: u16,
     pub rotate_flag: bool,
 }
 
+/// An icon drawn above an npc, such as a prayer or a skull.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct NpcHeadIcon {
+    /// The sprite archive containing the icon.
+    pub archive_id: u32,
+    /// The frame of the sprite archive.
+    pub sprite_index: u16,
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct NpcAnimationData {
@@ -69,31 +81,85 @@ pub struct NpcAnimationData {
     pub running_rotate_180: Option<u16>,
 }
 
-impl Definition for NpcDefinition {
-    fn new(id: u16, buffer: &[u8]) -> crate::Result<Self> {
-        let mut reader = BufReader::new(buffer);
-        let npc_def = decode_buffer(id, &mut reader)?;
+impl NpcDefinition {
+    /// Whether this npc has a chathead to render in dialogues.
+    pub fn has_chathead(&self) -> bool {
+        !self.model_data.chat_head_models.is_empty()
+    }
+
+    /// The models that make up this npc's chathead.
+    pub fn chathead_models(&self) -> &[u16] {
+        &self.model_data.chat_head_models
+    }
+
+    /// The actions that are set, in slot order.
+    pub fn actions_present(&self) -> Vec<&str> {
+        util::present(&self.actions)
+    }
+
+    /// The tiles occupied by this npc when its south-west tile is at `base`.
+    ///
+    /// An npc covers `size` by `size` tiles, npcs that don't set a size cover one tile.
+    /// Tiles are ordered row by row, starting at `base`.
+    pub fn footprint(&self, base: (i32, i32)) -> Vec<(i32, i32)> {
+        let size = self.size.max(1) as i32;
+        let (x, y) = base;
+
+        (0..size)
+            .flat_map(|dy| (0..size).map(move |dx| (x + dx, y + dy)))
+            .collect()
+    }
+}
+
+impl NpcModelData {
+    /// Pairs every color that is searched for with the color that replaces it.
+    pub fn recolor_pairs(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        debug_assert_eq!(self.recolor_find.len(), self.recolor_replace.len());
 
-        Ok(npc_def)
+        self.recolor_find
+            .iter()
+            .copied()
+            .zip(self.recolor_replace.iter().copied())
     }
 }
 
-#[allow(clippy::too_many_lines)]
-fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<NpcDefinition> {
-    let mut npc_def = NpcDefinition {
-        id,
-        interactable: true,
-        visible_on_minimap: true,
-        model_data: NpcModelData {
-            rotate_flag: true,
-            width_scale: 128,
-            height_scale: 128,
-            rotate_speed: 32,
-            ..NpcModelData::default()
-        },
-        ..NpcDefinition::default()
-    };
+impl OpcodeDefinition for NpcDefinition {
+    const NAME: &'static str = "npc";
+    const CURRENT_SINCE: Option<u16> = Some(210);
 
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            interactable: true,
+            visible_on_minimap: true,
+            model_data: NpcModelData {
+                rotate_flag: true,
+                width_scale: 128,
+                height_scale: 128,
+                rotate_speed: 32,
+                ..NpcModelData::default()
+            },
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader, layout)
+    }
+}
+
+/// Before revision 210 opcode 102 held a single head icon instead of a bitfield of icons,
+/// the legacy layout stores it in `head_icon`.
+#[allow(clippy::too_many_lines)]
+fn decode_opcodes(
+    npc_def: &mut NpcDefinition,
+    reader: &mut Cursor<&[u8]>,
+    layout: Layout,
+) -> io::Result<Option<u8>> {
     loop {
         let opcode = reader.read_u8()?;
 
@@ -175,26 +241,21 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<NpcDefini
             101 => {
                 npc_def.model_data.contrast = reader.read_u8()?;
             }
+            102 if layout == Layout::Legacy => {
+                npc_def.model_data.head_icon = Some(reader.read_u16()?);
+            }
             102 => {
-                // npc_def.model_data.head_icon = Some(reader.read_u16()?);
-                let bitfield = reader.read_u8()? as i32;
-                let mut len: i32 = 0;
-                let mut bitfield_clone = bitfield.clone();
-                
-                while(bitfield_clone != 0) {
-                    bitfield_clone >>= 1;
-                    len += 1;
-                }
-                for i in 0..len { 
-					if ((bitfield & 1 << i) == 0)
-					{
-					}
-					else
-					{
-                        // Correct length reads, not doing anything. TODO
-						let _ = reader.read_smart()?;
-						let _ = reader.read_smart_u16()? - 1;
-					}
+                // Every set bit of the bitfield is a slot with an icon.
+                let bitfield = reader.read_u8()?;
+                let len = u8::BITS - bitfield.leading_zeros();
+                npc_def.model_data.head_icons = Vec::with_capacity(len as usize);
+                for slot in 0..len {
+                    let head_icon = if bitfield & 1 << slot == 0 {
+                        None
+                    } else {
+                        read_head_icon(reader)?
+                    };
+                    npc_def.model_data.head_icons.push(head_icon);
                 }
             }
             103 => {
@@ -202,14 +263,14 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<NpcDefini
             }
             106 => {
                 let varbit_id = reader.read_u16()?;
-                npc_def.varbit_id = if varbit_id == std::u16::MAX {
+                npc_def.varbit_id = if varbit_id == u16::MAX {
                     None
                 } else {
                     Some(varbit_id)
                 };
 
                 let varp_index = reader.read_u16()?;
-                npc_def.varp_index = if varp_index == std::u16::MAX {
+                npc_def.varp_index = if varp_index == u16::MAX {
                     None
                 } else {
                     Some(varp_index)
@@ -247,14 +308,14 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<NpcDefini
             }
             118 => {
                 let varbit_id = reader.read_u16()?;
-                npc_def.varbit_id = if varbit_id == std::u16::MAX {
+                npc_def.varbit_id = if varbit_id == u16::MAX {
                     None
                 } else {
                     Some(varbit_id)
                 };
 
                 let varp_index = reader.read_u16()?;
-                npc_def.varp_index = if varp_index == std::u16::MAX {
+                npc_def.varp_index = if varp_index == u16::MAX {
                     None
                 } else {
                     Some(varp_index)
@@ -274,9 +335,30 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<NpcDefini
             249 => {
                 npc_def.params = util::read_parameters(reader)?;
             }
-            unknown => eprintln!("Unknown opcode {} when parsing npc definition", unknown),
+            opcode => return Ok(Some(opcode)),
         }
     }
 
-    Ok(npc_def)
+    Ok(None)
+}
+
+/// Reads an archive id as a big smart and a sprite index as a smart minus one.
+fn read_head_icon(reader: &mut Cursor<&[u8]>) -> io::Result<Option<NpcHeadIcon>> {
+    let archive_id = reader.read_smart()?;
+
+    let byte = reader.read_u8()?;
+    let sprite_index = if byte < 128 {
+        i32::from(byte) - 1
+    } else {
+        i32::from(u16::from_be_bytes([byte, reader.read_u8()?])) - 0x8001
+    };
+
+    if archive_id == 0x7FFF || sprite_index < 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(NpcHeadIcon {
+        archive_id,
+        sprite_index: sprite_index as u16,
+    }))
 }
@@ -1,9 +1,9 @@
-use std::{collections::HashMap, io, io::BufReader};
+use std::{collections::HashMap, io, io::Cursor};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use super::Definition;
+use super::{Layout, OpcodeDefinition};
 use crate::{extension::ReadExt, util};
 
 /// Contains all the information about a certain object fetched from the cache through
@@ -14,17 +14,28 @@ pub struct ObjectDefinition {
     pub id: u16,
     pub name: String,
     pub config_id: Option<u16>,
+    /// The [map area](super::AreaDefinition) whose icon marks this object on the minimap
+    /// and world map, e.g. the bank icon on bank booths.
     pub map_area_id: Option<u16>,
-    pub map_scene_id: u16,
-    pub animation_id: u16,
+    /// The scene sprite drawn on the minimap underneath this object, e.g. trees and altars.
+    pub map_scene_id: Option<u16>,
+    pub animation_id: Option<u16>,
     pub solid: bool,
     pub shadow: bool,
     pub obstruct_ground: bool,
+    pub delay_shading: bool,
     pub supports_items: Option<u8>,
     pub actions: [String; 5],
     pub interact_type: u8,
     pub rotated: bool,
-    pub ambient_sound_id: u16,
+    pub ambient_sound_id: Option<u16>,
+    /// How far the ambient sound can be heard, in tiles.
+    pub ambient_sound_distance: u8,
+    pub ambient_sound_retain: u8,
+    /// Ambient sounds that are picked at random, a new one is picked after a random
+    /// number of ticks within `ambient_sound_change_ticks`.
+    pub ambient_sound_ids: Vec<u16>,
+    pub ambient_sound_change_ticks: (u16, u16),
     pub blocks_projectile: bool,
     pub wall_or_door: Option<u8>,
     pub contoured_ground: Option<u8>,
@@ -45,51 +56,139 @@ pub struct ObjectModelData {
     pub retexture_replace: Vec<u16>,
     pub size_x: u8,
     pub size_y: u8,
-    pub offset_x: u16,
-    pub offset_y: u16,
-    pub offset_z: u16,
+    /// How far the model is translated from the tile, set by opcodes 70 to 72.
+    ///
+    /// The offsets are signed, e.g. to pull a wall decoration back against its wall.
+    pub offset_x: i16,
+    pub offset_y: i16,
+    pub offset_z: i16,
     pub model_size_x: u16,
     pub model_size_y: u16,
     pub model_size_z: u16,
     pub varp_id: Option<u16>,
     pub ambient: u8,
     pub contrast: u8,
-    pub decord_displacement: u8,
+    /// How far wall decorations (shapes 4 to 8) sit out from their wall, 16 by default.
+    #[cfg_attr(feature = "serde", serde(alias = "decord_displacement"))]
+    pub decor_displacement: u8,
     pub merge_normals: bool,
     pub blocking_mask: u8,
 }
 
-impl Definition for ObjectDefinition {
-    fn new(id: u16, buffer: &[u8]) -> crate::Result<Self> {
-        let mut reader = BufReader::new(buffer);
-        let mut obj_def = decode_buffer(id, &mut reader)?;
-        post(&mut obj_def);
+/// The map layer an object is placed on, derived from its shape.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ObjectType {
+    /// Shapes 0 to 3: straight walls, wall corners and wall edges.
+    Wall,
+    /// Shapes 4 to 8: decorations hanging on walls such as banners and torches.
+    WallDecoration,
+    /// Shapes 9 to 21: diagonal walls, interactable scenery and roofs.
+    Scenery,
+    /// Shape 22: decorations lying on the ground such as flowers and rugs.
+    GroundDecoration,
+}
 
-        Ok(obj_def)
+impl ObjectType {
+    /// Classifies a shape id, also known as the location type.
+    pub const fn from_shape(shape: u8) -> Self {
+        match shape {
+            0..=3 => Self::Wall,
+            4..=8 => Self::WallDecoration,
+            22 => Self::GroundDecoration,
+            _ => Self::Scenery,
+        }
     }
 }
 
-fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ObjectDefinition> {
-    let mut obj_def = ObjectDefinition {
-        id,
-        interact_type: 2,
-        blocks_projectile: true,
-        solid: true,
-        model_data: ObjectModelData {
-            decord_displacement: 16,
-            size_x: 1,
-            size_y: 1,
-            model_size_x: 128,
-            model_size_y: 128,
-            model_size_z: 128,
-            ..ObjectModelData::default()
-        },
-        ..ObjectDefinition::default()
-    };
+impl ObjectDefinition {
+    /// Whether this object plays an animation, e.g. fires and fountains.
+    pub const fn supports_animation(&self) -> bool {
+        self.animation_id.is_some()
+    }
 
+    /// Whether the model is mirrored, which is stored as `rotated` (opcode 62).
+    pub const fn mirror(&self) -> bool {
+        self.rotated
+    }
+
+    /// Whether the model is stretched to follow the height of the terrain.
+    pub const fn contours_ground(&self) -> bool {
+        self.contoured_ground.is_some()
+    }
+
+    /// Classifies this object by its first shape.
+    ///
+    /// Objects without explicit shapes are scenery (shape 10).
+    pub fn object_type(&self) -> ObjectType {
+        ObjectType::from_shape(self.model_data.types.first().copied().unwrap_or(10))
+    }
+}
+
+impl ObjectModelData {
+    /// Pairs every color that is searched for with the color that replaces it.
+    pub fn recolor_pairs(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        debug_assert_eq!(self.recolor_find.len(), self.recolor_replace.len());
+
+        self.recolor_find
+            .iter()
+            .copied()
+            .zip(self.recolor_replace.iter().copied())
+    }
+}
+
+impl OpcodeDefinition for ObjectDefinition {
+    const NAME: &'static str = "object";
+    const CURRENT_SINCE: Option<u16> = Some(220);
+
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            interact_type: 2,
+            blocks_projectile: true,
+            solid: true,
+            model_data: ObjectModelData {
+                decor_displacement: 16,
+                size_x: 1,
+                size_y: 1,
+                model_size_x: 128,
+                model_size_y: 128,
+                model_size_z: 128,
+                ..ObjectModelData::default()
+            },
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader, layout)
+    }
+
+    fn finish(&mut self) {
+        post(self);
+    }
+}
+
+/// Opcodes whose payload is read and thrown away, with their length in bytes.
+const SKIPPED_OPCODES: &[(u8, usize)] = &[(89, 0)];
+
+/// Before revision 220 the ambient sounds of opcodes 78 and 79 had no retain byte.
+fn decode_opcodes(
+    obj_def: &mut ObjectDefinition,
+    reader: &mut Cursor<&[u8]>,
+    layout: Layout,
+) -> io::Result<Option<u8>> {
     loop {
         let opcode = reader.read_u8()?;
 
+        if util::skip_opcode(reader, SKIPPED_OPCODES, opcode)? {
+            continue;
+        }
+
         match opcode {
             0 => break,
             1 => {
@@ -131,15 +230,22 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ObjectDef
             22 => {
                 obj_def.model_data.merge_normals = true;
             }
-            23 => { /* skip */ }
+            23 => {
+                obj_def.delay_shading = true;
+            }
             24 => {
-                obj_def.animation_id = reader.read_u16()?;
+                let animation_id = reader.read_u16()?;
+                obj_def.animation_id = if animation_id == u16::MAX {
+                    None
+                } else {
+                    Some(animation_id)
+                };
             }
             27 => {
                 obj_def.interact_type = 1;
             }
             28 => {
-                obj_def.model_data.decord_displacement = reader.read_u8()?;
+                obj_def.model_data.decor_displacement = reader.read_u8()?;
             }
             29 => {
                 obj_def.model_data.ambient = reader.read_u8()?;
@@ -186,19 +292,19 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ObjectDef
                 obj_def.model_data.model_size_y = reader.read_u16()?;
             }
             68 => {
-                obj_def.map_scene_id = reader.read_u16()?;
+                obj_def.map_scene_id = Some(reader.read_u16()?);
             }
             69 => {
                 obj_def.model_data.blocking_mask = reader.read_u8()?;
             }
             70 => {
-                obj_def.model_data.offset_x = reader.read_u16()?;
+                obj_def.model_data.offset_x = reader.read_i16()?;
             }
             71 => {
-                obj_def.model_data.offset_z = reader.read_u16()?;
+                obj_def.model_data.offset_z = reader.read_i16()?;
             }
             72 => {
-                obj_def.model_data.offset_y = reader.read_u16()?;
+                obj_def.model_data.offset_y = reader.read_i16()?;
             }
             73 => {
                 obj_def.obstruct_ground = true;
@@ -211,13 +317,13 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ObjectDef
             }
             77 => {
                 let varp_id = reader.read_u16()?;
-                obj_def.model_data.varp_id = if varp_id == std::u16::MAX {
+                obj_def.model_data.varp_id = if varp_id == u16::MAX {
                     None
                 } else {
                     Some(varp_id)
                 };
                 let config_id = reader.read_u16()?;
-                obj_def.config_id = if config_id == std::u16::MAX {
+                obj_def.config_id = if config_id == u16::MAX {
                     None
                 } else {
                     Some(config_id)
@@ -229,20 +335,23 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ObjectDef
                 }
             }
             78 => {
-                obj_def.ambient_sound_id = reader.read_u16()?;
-                reader.read_u8()?;
-                // Rev220 new
-                reader.read_u8()?;
+                obj_def.ambient_sound_id = Some(reader.read_u16()?);
+                obj_def.ambient_sound_distance = reader.read_u8()?;
+                if layout == Layout::Current {
+                    obj_def.ambient_sound_retain = reader.read_u8()?;
+                }
             }
             79 => {
-                reader.read_u16()?;
-                reader.read_u16()?;
-                reader.read_u8()?;
-                // Rev220 new
-                reader.read_u8()?;
+                let min = reader.read_u16()?;
+                let max = reader.read_u16()?;
+                obj_def.ambient_sound_change_ticks = (min, max);
+                obj_def.ambient_sound_distance = reader.read_u8()?;
+                if layout == Layout::Current {
+                    obj_def.ambient_sound_retain = reader.read_u8()?;
+                }
                 let len = reader.read_u8()?;
                 for _ in 0..len {
-                    reader.read_u16()?;
+                    obj_def.ambient_sound_ids.push(reader.read_u16()?);
                 }
             }
             81 => {
@@ -251,17 +360,16 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ObjectDef
             82 => {
                 obj_def.map_area_id = Some(reader.read_u16()?);
             }
-            89 => { /* skip */ }
             92 => {
                 let varp_id = reader.read_u16()?;
-                obj_def.model_data.varp_id = if varp_id == std::u16::MAX {
+                obj_def.model_data.varp_id = if varp_id == u16::MAX {
                     None
                 } else {
                     Some(varp_id)
                 };
 
                 let config_id = reader.read_u16()?;
-                obj_def.config_id = if config_id == std::u16::MAX {
+                obj_def.config_id = if config_id == u16::MAX {
                     None
                 } else {
                     Some(config_id)
@@ -278,11 +386,11 @@ fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<ObjectDef
             249 => {
                 obj_def.params = util::read_parameters(reader)?;
             }
-            _ => unreachable!(),
+            opcode => return Ok(Some(opcode)),
         }
     }
 
-    Ok(obj_def)
+    Ok(None)
 }
 
 fn post(obj_def: &mut ObjectDefinition) {
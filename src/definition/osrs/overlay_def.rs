@@ -0,0 +1,72 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Layout, OpcodeDefinition, TextureDefinition};
+use crate::{extension::ReadExt, loader::osrs::TextureLoader};
+
+/// Contains all the information about a certain overlay fetched from the cache through
+/// the [OverlayLoader](../../loader/osrs/struct.OverlayLoader.html).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct OverlayDefinition {
+    pub id: u16,
+    pub color: u32,
+    pub texture_id: Option<u8>,
+    pub hide_underlay: bool,
+    pub secondary_color: Option<u32>,
+}
+
+impl OverlayDefinition {
+    /// Resolves the texture this overlay is painted with, if any.
+    pub fn texture<'a>(&self, loader: &'a TextureLoader) -> Option<&'a TextureDefinition> {
+        loader.load(self.texture_id? as u16)
+    }
+}
+
+impl OpcodeDefinition for OverlayDefinition {
+    const NAME: &'static str = "overlay";
+
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            hide_underlay: true,
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        _layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader)
+    }
+}
+
+fn decode_opcodes(
+    overlay_def: &mut OverlayDefinition,
+    reader: &mut Cursor<&[u8]>,
+) -> io::Result<Option<u8>> {
+    loop {
+        let opcode = reader.read_u8()?;
+
+        match opcode {
+            0 => break,
+            1 => {
+                overlay_def.color = reader.read_u24()?;
+            }
+            2 => {
+                overlay_def.texture_id = Some(reader.read_u8()?);
+            }
+            5 => overlay_def.hide_underlay = false,
+            7 => {
+                overlay_def.secondary_color = Some(reader.read_u24()?);
+            }
+            opcode => return Ok(Some(opcode)),
+        }
+    }
+
+    Ok(None)
+}
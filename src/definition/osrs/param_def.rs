@@ -0,0 +1,111 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Layout, OpcodeDefinition};
+use crate::extension::ReadExt;
+
+/// Contains all the information about a certain param fetched from the cache through
+/// the [ParamLoader](../../loader/osrs/struct.ParamLoader.html).
+///
+/// Params are the keys of the `params` maps on items, npcs and objects. The config holds
+/// the type of the param and the value used when a definition doesn't set it.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ParamDefinition {
+    pub id: u16,
+    /// The script type character of the value, `s` for strings.
+    pub value_type: u8,
+    pub default_int: i32,
+    pub default_string: String,
+    pub auto_disable: bool,
+}
+
+/// The value of a param, typed according to its [`ParamDefinition`].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ParamValue {
+    Int(i32),
+    String(String),
+}
+
+impl Default for ParamDefinition {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            id: 0,
+            value_type: 0,
+            default_int: 0,
+            default_string: String::from("null"),
+            auto_disable: true,
+        }
+    }
+}
+
+impl ParamDefinition {
+    /// Whether the values of this param are strings.
+    pub fn is_string(&self) -> bool {
+        self.value_type == b's'
+    }
+
+    /// The value used when a definition doesn't set this param.
+    pub fn default_value(&self) -> ParamValue {
+        if self.is_string() {
+            ParamValue::String(self.default_string.clone())
+        } else {
+            ParamValue::Int(self.default_int)
+        }
+    }
+
+    /// Types a raw value from a definition's `params` map according to this param.
+    ///
+    /// Integer values that can't be parsed fall back to the default value.
+    pub fn value(&self, raw: &str) -> ParamValue {
+        if self.is_string() {
+            ParamValue::String(raw.to_owned())
+        } else {
+            raw.parse()
+                .map_or_else(|_| self.default_value(), ParamValue::Int)
+        }
+    }
+}
+
+impl OpcodeDefinition for ParamDefinition {
+    const NAME: &'static str = "param";
+
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        _layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader)
+    }
+}
+
+fn decode_opcodes(
+    param_def: &mut ParamDefinition,
+    reader: &mut Cursor<&[u8]>,
+) -> io::Result<Option<u8>> {
+    loop {
+        let opcode = reader.read_u8()?;
+
+        match opcode {
+            0 => break,
+            1 => param_def.value_type = reader.read_u8()?,
+            2 => param_def.default_int = reader.read_i32()?,
+            4 => param_def.auto_disable = false,
+            5 => param_def.default_string = reader.read_string()?,
+            opcode => return Ok(Some(opcode)),
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,141 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Layout, OpcodeDefinition};
+use crate::extension::ReadExt;
+
+/// Contains all the information about a certain animation fetched from the cache through
+/// the [SequenceLoader](../../loader/osrs/struct.SequenceLoader.html).
+///
+/// Every frame id packs the frame archive in the upper 16 bits and the file within that
+/// archive in the lower 16 bits.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct SequenceDefinition {
+    pub id: u16,
+    /// The duration of every frame in client ticks.
+    pub frame_lengths: Vec<u16>,
+    pub frame_ids: Vec<u32>,
+    pub chat_frame_ids: Vec<u32>,
+    pub frame_step: Option<u16>,
+    pub interleave_order: Vec<u8>,
+    pub stretches: bool,
+    pub forced_priority: u8,
+    pub left_hand_item: Option<u16>,
+    pub right_hand_item: Option<u16>,
+    pub max_loops: u8,
+    pub precedence_animating: Option<u8>,
+    pub priority: Option<u8>,
+    pub reply_mode: u8,
+    pub sound_effects: Vec<u32>,
+}
+
+impl Default for SequenceDefinition {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            id: 0,
+            frame_lengths: Vec::new(),
+            frame_ids: Vec::new(),
+            chat_frame_ids: Vec::new(),
+            frame_step: None,
+            interleave_order: Vec::new(),
+            stretches: false,
+            forced_priority: 5,
+            left_hand_item: None,
+            right_hand_item: None,
+            max_loops: 99,
+            precedence_animating: None,
+            priority: None,
+            reply_mode: 2,
+            sound_effects: Vec::new(),
+        }
+    }
+}
+
+impl SequenceDefinition {
+    /// The duration of one playthrough of the animation in client ticks.
+    pub fn duration(&self) -> u32 {
+        self.frame_lengths.iter().map(|&len| u32::from(len)).sum()
+    }
+}
+
+impl OpcodeDefinition for SequenceDefinition {
+    const NAME: &'static str = "sequence";
+
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        _layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader)
+    }
+}
+
+fn decode_opcodes(
+    sequence_def: &mut SequenceDefinition,
+    reader: &mut Cursor<&[u8]>,
+) -> io::Result<Option<u8>> {
+    loop {
+        let opcode = reader.read_u8()?;
+
+        match opcode {
+            0 => break,
+            1 => {
+                let len = reader.read_u16()?;
+                for _ in 0..len {
+                    sequence_def.frame_lengths.push(reader.read_u16()?);
+                }
+                sequence_def.frame_ids = read_frame_ids(reader, len as usize)?;
+            }
+            2 => sequence_def.frame_step = Some(reader.read_u16()?),
+            3 => {
+                let len = reader.read_u8()?;
+                sequence_def.interleave_order = reader.read_bytes(len as usize)?;
+            }
+            4 => sequence_def.stretches = true,
+            5 => sequence_def.forced_priority = reader.read_u8()?,
+            6 => sequence_def.left_hand_item = Some(reader.read_u16()?),
+            7 => sequence_def.right_hand_item = Some(reader.read_u16()?),
+            8 => sequence_def.max_loops = reader.read_u8()?,
+            9 => sequence_def.precedence_animating = Some(reader.read_u8()?),
+            10 => sequence_def.priority = Some(reader.read_u8()?),
+            11 => sequence_def.reply_mode = reader.read_u8()?,
+            12 => {
+                let len = reader.read_u8()?;
+                sequence_def.chat_frame_ids = read_frame_ids(reader, len as usize)?;
+            }
+            13 => {
+                let len = reader.read_u8()?;
+                for _ in 0..len {
+                    sequence_def.sound_effects.push(reader.read_u24()?);
+                }
+            }
+            opcode => return Ok(Some(opcode)),
+        }
+    }
+
+    Ok(None)
+}
+
+/// Frame ids are stored as all the lower halves followed by all the upper halves.
+fn read_frame_ids(reader: &mut Cursor<&[u8]>, len: usize) -> io::Result<Vec<u32>> {
+    let mut frame_ids = Vec::with_capacity(len);
+    for _ in 0..len {
+        frame_ids.push(u32::from(reader.read_u16()?));
+    }
+    for frame_id in &mut frame_ids {
+        *frame_id |= u32::from(reader.read_u16()?) << 16;
+    }
+
+    Ok(frame_ids)
+}
@@ -0,0 +1,65 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::Definition;
+use crate::extension::ReadExt;
+
+/// The transform type that offsets the origin of the following transforms.
+pub const TRANSFORM_ORIGIN: u8 = 0;
+/// The transform type that scales the vertices of a group.
+pub const TRANSFORM_SCALE: u8 = 3;
+
+/// Contains the bone groups of a certain skeleton, used to decode animation frames
+/// through the [FrameLoader](../../loader/osrs/struct.FrameLoader.html).
+///
+/// Every transform slot of a frame applies to the vertex groups at the same position
+/// in `groups`, the way it is applied depends on the type at that position in `types`:
+/// 0 moves the origin, 1 translates, 2 rotates, 3 scales and 5 changes transparency.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct SkeletonDefinition {
+    pub id: u16,
+    pub types: Vec<u8>,
+    pub groups: Vec<Vec<u8>>,
+}
+
+impl SkeletonDefinition {
+    /// The number of transform slots in this skeleton.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+}
+
+impl Definition for SkeletonDefinition {
+    fn new(id: u16, buffer: &[u8]) -> crate::Result<Self> {
+        let mut reader = Cursor::new(buffer);
+        let skeleton_def = decode_buffer(id, &mut reader)?;
+
+        Ok(skeleton_def)
+    }
+}
+
+fn decode_buffer(id: u16, reader: &mut Cursor<&[u8]>) -> io::Result<SkeletonDefinition> {
+    let len = reader.read_u8()? as usize;
+    let types = reader.read_bytes(len)?;
+
+    let mut group_lens = Vec::with_capacity(len);
+    for _ in 0..len {
+        group_lens.push(reader.read_u8()? as usize);
+    }
+
+    let mut groups = Vec::with_capacity(len);
+    for group_len in group_lens {
+        groups.push(reader.read_bytes(group_len)?);
+    }
+
+    Ok(SkeletonDefinition { id, types, groups })
+}
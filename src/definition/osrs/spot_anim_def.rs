@@ -0,0 +1,115 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Layout, OpcodeDefinition, SequenceDefinition};
+use crate::{extension::ReadExt, loader::osrs::SequenceLoader};
+
+/// Contains all the information about a certain spot animation fetched from the cache
+/// through the [SpotAnimLoader](../../loader/osrs/struct.SpotAnimLoader.html).
+///
+/// Spot animations, also known as graphics, are the animated models drawn on top of
+/// characters and tiles such as spell effects.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct SpotAnimDefinition {
+    pub id: u16,
+    pub model_id: u16,
+    pub animation_id: Option<u16>,
+    pub resize_x: u16,
+    pub resize_y: u16,
+    pub rotation: u16,
+    pub ambient: u8,
+    pub contrast: u8,
+    pub recolor_find: Vec<u16>,
+    pub recolor_replace: Vec<u16>,
+    pub retexture_find: Vec<u16>,
+    pub retexture_replace: Vec<u16>,
+}
+
+impl Default for SpotAnimDefinition {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            id: 0,
+            model_id: 0,
+            animation_id: None,
+            resize_x: 128,
+            resize_y: 128,
+            rotation: 0,
+            ambient: 0,
+            contrast: 0,
+            recolor_find: Vec::new(),
+            recolor_replace: Vec::new(),
+            retexture_find: Vec::new(),
+            retexture_replace: Vec::new(),
+        }
+    }
+}
+
+impl SpotAnimDefinition {
+    /// Looks up the animation that is played on the model of this spot animation.
+    pub fn animation<'a>(
+        &self,
+        sequence_loader: &'a SequenceLoader,
+    ) -> Option<&'a SequenceDefinition> {
+        sequence_loader.load(self.animation_id?)
+    }
+}
+
+impl OpcodeDefinition for SpotAnimDefinition {
+    const NAME: &'static str = "spotanim";
+
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        _layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader)
+    }
+}
+
+fn decode_opcodes(
+    spot_anim_def: &mut SpotAnimDefinition,
+    reader: &mut Cursor<&[u8]>,
+) -> io::Result<Option<u8>> {
+    loop {
+        let opcode = reader.read_u8()?;
+
+        match opcode {
+            0 => break,
+            1 => spot_anim_def.model_id = reader.read_u16()?,
+            2 => spot_anim_def.animation_id = Some(reader.read_u16()?),
+            4 => spot_anim_def.resize_x = reader.read_u16()?,
+            5 => spot_anim_def.resize_y = reader.read_u16()?,
+            6 => spot_anim_def.rotation = reader.read_u16()?,
+            7 => spot_anim_def.ambient = reader.read_u8()?,
+            8 => spot_anim_def.contrast = reader.read_u8()?,
+            40 => {
+                let len = reader.read_u8()?;
+                for _ in 0..len {
+                    spot_anim_def.recolor_find.push(reader.read_u16()?);
+                    spot_anim_def.recolor_replace.push(reader.read_u16()?);
+                }
+            }
+            41 => {
+                let len = reader.read_u8()?;
+                for _ in 0..len {
+                    spot_anim_def.retexture_find.push(reader.read_u16()?);
+                    spot_anim_def.retexture_replace.push(reader.read_u16()?);
+                }
+            }
+            opcode => return Ok(Some(opcode)),
+        }
+    }
+
+    Ok(None)
+}
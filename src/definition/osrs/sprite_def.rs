@@ -0,0 +1,145 @@
+use std::io;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::Definition;
+use crate::extension::ReadExt;
+
+/// Contains all the frames of a certain sprite fetched from the cache through
+/// the [SpriteLoader](../../loader/osrs/struct.SpriteLoader.html).
+///
+/// Most sprites consist of a single frame, animated sprites contain one frame per
+/// animation step. Every frame is positioned within `max_width` x `max_height`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct SpriteDefinition {
+    pub id: u16,
+    pub max_width: u16,
+    pub max_height: u16,
+    pub frames: Vec<SpriteFrame>,
+}
+
+/// A single frame of a sprite, its pixels are stored as ARGB in row-major order.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct SpriteFrame {
+    pub offset_x: u16,
+    pub offset_y: u16,
+    pub width: u16,
+    pub height: u16,
+    pub pixels: Vec<u32>,
+}
+
+impl SpriteDefinition {
+    /// The amount of frames in this sprite, more than one for animated sprites.
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+impl Definition for SpriteDefinition {
+    fn new(id: u16, buffer: &[u8]) -> crate::Result<Self> {
+        let sprite_def = decode_buffer(id, buffer)?;
+
+        Ok(sprite_def)
+    }
+}
+
+fn decode_buffer(id: u16, buffer: &[u8]) -> io::Result<SpriteDefinition> {
+    let eof = || io::Error::from(io::ErrorKind::UnexpectedEof);
+
+    let len = buffer.len();
+    let frame_count = buffer
+        .get(len.saturating_sub(2)..)
+        .ok_or_else(eof)?
+        .read_u16()? as usize;
+
+    // The header is stored at the end of the buffer, right before the frame count.
+    let header_pos = len.checked_sub(7 + frame_count * 8).ok_or_else(eof)?;
+    let mut header = &buffer[header_pos..];
+
+    let mut sprite_def = SpriteDefinition {
+        id,
+        max_width: header.read_u16()?,
+        max_height: header.read_u16()?,
+        frames: vec![SpriteFrame::default(); frame_count],
+    };
+    let palette_len = header.read_u8()? as usize + 1;
+
+    for frame in &mut sprite_def.frames {
+        frame.offset_x = header.read_u16()?;
+    }
+    for frame in &mut sprite_def.frames {
+        frame.offset_y = header.read_u16()?;
+    }
+    for frame in &mut sprite_def.frames {
+        frame.width = header.read_u16()?;
+    }
+    for frame in &mut sprite_def.frames {
+        frame.height = header.read_u16()?;
+    }
+
+    // The palette precedes the header, index 0 is reserved for transparency.
+    let palette_pos = header_pos
+        .checked_sub((palette_len - 1) * 3)
+        .ok_or_else(eof)?;
+    let mut reader = &buffer[palette_pos..];
+    let mut palette = vec![0; palette_len];
+    for color in palette.iter_mut().skip(1) {
+        *color = reader.read_u24()?;
+        if *color == 0 {
+            *color = 1;
+        }
+    }
+
+    let mut reader = &buffer[..palette_pos];
+    for frame in &mut sprite_def.frames {
+        let width = frame.width as usize;
+        let height = frame.height as usize;
+        let flags = reader.read_u8()?;
+
+        let indices = read_pixels(&mut reader, width, height, flags & 1 != 0)?;
+        let alphas = if flags & 2 == 0 {
+            indices
+                .iter()
+                .map(|&index| if index == 0 { 0 } else { 0xFF })
+                .collect()
+        } else {
+            read_pixels(&mut reader, width, height, flags & 1 != 0)?
+        };
+
+        frame.pixels = indices
+            .iter()
+            .zip(alphas)
+            .map(|(&index, alpha)| {
+                let color = palette.get(index as usize).copied().unwrap_or_default();
+                (alpha as u32) << 24 | color
+            })
+            .collect();
+    }
+
+    Ok(sprite_def)
+}
+
+/// Reads a byte per pixel, either stored row by row or column by column.
+fn read_pixels(
+    reader: &mut &[u8],
+    width: usize,
+    height: usize,
+    column_major: bool,
+) -> io::Result<Vec<u8>> {
+    let mut pixels = vec![0; width * height];
+
+    if column_major {
+        for x in 0..width {
+            for y in 0..height {
+                pixels[width * y + x] = reader.read_u8()?;
+            }
+        }
+    } else {
+        io::Read::read_exact(reader, &mut pixels)?;
+    }
+
+    Ok(pixels)
+}
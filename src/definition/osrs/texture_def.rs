@@ -0,0 +1,143 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Definition, SpriteFrame};
+use crate::{error::ReadError, extension::ReadExt, loader::osrs::SpriteLoader};
+
+/// Contains all the information about a certain texture fetched from the cache through
+/// the [TextureLoader](../../loader/osrs/struct.TextureLoader.html).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct TextureDefinition {
+    pub id: u16,
+    pub average_color: u16,
+    pub opaque: bool,
+    pub sprite_ids: Vec<u16>,
+    pub sprite_types: Vec<u8>,
+    pub transforms: Vec<u8>,
+    pub colors: Vec<i32>,
+    pub animation_direction: u8,
+    pub animation_speed: u8,
+}
+
+impl TextureDefinition {
+    /// Renders the texture as a `size` by `size` tile of RGBA pixels, row by row.
+    ///
+    /// Every sprite is scaled to the tile with nearest neighbour sampling and drawn over
+    /// the previous one. Colors with the tint flag (`0x03` in the upper byte) are applied
+    /// to the grey pixels of their sprite. Transparent pixels keep an alpha of zero unless
+    /// the texture is opaque.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReadError::SpriteNotFound` if a sprite is missing or has no frames.
+    pub fn render(&self, sprite_loader: &SpriteLoader, size: u32) -> crate::Result<Vec<u8>> {
+        let size = size as usize;
+        let mut pixels = vec![0_u32; size * size];
+
+        for (i, &sprite_id) in self.sprite_ids.iter().enumerate() {
+            let sprite = sprite_loader
+                .load(sprite_id)
+                .ok_or(ReadError::SpriteNotFound { id: sprite_id })?;
+            let frame = sprite
+                .frames
+                .first()
+                .ok_or(ReadError::SpriteNotFound { id: sprite_id })?;
+            let tint = self.colors.get(i).copied().filter(|color| color >> 24 == 3);
+
+            // Frames are positioned within the full sprite dimensions.
+            let width = (sprite.max_width as usize).max(1);
+            let height = (sprite.max_height as usize).max(1);
+            for y in 0..size {
+                for x in 0..size {
+                    let pixel = frame_pixel(frame, x * width / size, y * height / size);
+                    if pixel >> 24 == 0 && i > 0 {
+                        continue;
+                    }
+
+                    pixels[y * size + x] = match tint {
+                        Some(color) => apply_tint(pixel, color as u32),
+                        None => pixel,
+                    };
+                }
+            }
+        }
+
+        Ok(pixels
+            .into_iter()
+            .flat_map(|pixel| {
+                let [alpha, red, green, blue] = pixel.to_be_bytes();
+                let alpha = if self.opaque { 0xFF } else { alpha };
+                [red, green, blue, alpha]
+            })
+            .collect())
+    }
+}
+
+/// The ARGB pixel of a frame at a position within the full sprite, transparent outside the frame.
+fn frame_pixel(frame: &SpriteFrame, x: usize, y: usize) -> u32 {
+    let x = x.checked_sub(frame.offset_x as usize);
+    let y = y.checked_sub(frame.offset_y as usize);
+    match (x, y) {
+        (Some(x), Some(y)) if x < frame.width as usize && y < frame.height as usize => {
+            frame.pixels[y * frame.width as usize + x]
+        }
+        _ => 0,
+    }
+}
+
+/// Multiplies a grey pixel with the tint color, other pixels are left as is.
+fn apply_tint(pixel: u32, color: u32) -> u32 {
+    let rgb = pixel & 0xFF_FFFF;
+    if rgb >> 8 != rgb & 0xFFFF {
+        return pixel;
+    }
+
+    let grey = rgb & 0xFF;
+    let red_blue = (((color & 0xFF_00FF) * grey) >> 8) & 0xFF_00FF;
+    let green = (((color >> 8) & 0xFF) * grey) & 0xFF00;
+    (pixel & 0xFF00_0000) | red_blue | green
+}
+
+impl Definition for TextureDefinition {
+    fn new(id: u16, buffer: &[u8]) -> crate::Result<Self> {
+        let mut reader = Cursor::new(buffer);
+        let texture_def = decode_buffer(id, &mut reader)?;
+
+        Ok(texture_def)
+    }
+}
+
+fn decode_buffer(id: u16, reader: &mut Cursor<&[u8]>) -> io::Result<TextureDefinition> {
+    let mut texture_def = TextureDefinition {
+        id,
+        average_color: reader.read_u16()?,
+        opaque: reader.read_u8()? != 0,
+        ..TextureDefinition::default()
+    };
+
+    let len = reader.read_u8()? as usize;
+    for _ in 0..len {
+        texture_def.sprite_ids.push(reader.read_u16()?);
+    }
+
+    if len > 1 {
+        for _ in 0..len - 1 {
+            texture_def.sprite_types.push(reader.read_u8()?);
+        }
+        for _ in 0..len - 1 {
+            texture_def.transforms.push(reader.read_u8()?);
+        }
+    }
+
+    for _ in 0..len {
+        texture_def.colors.push(reader.read_i32()?);
+    }
+
+    texture_def.animation_direction = reader.read_u8()?;
+    texture_def.animation_speed = reader.read_u8()?;
+
+    Ok(texture_def)
+}
@@ -0,0 +1,60 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Layout, OpcodeDefinition};
+use crate::extension::ReadExt;
+
+/// Contains all the information about a certain varbit fetched from the cache through
+/// the [VarbitLoader](../../loader/osrs/struct.VarbitLoader.html).
+///
+/// A varbit is a bit range within a varp, both bits are inclusive.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct VarbitDefinition {
+    pub id: u16,
+    pub varp_index: u16,
+    pub least_significant_bit: u8,
+    pub most_significant_bit: u8,
+}
+
+impl OpcodeDefinition for VarbitDefinition {
+    const NAME: &'static str = "varbit";
+
+    fn with_defaults(id: u16) -> Self {
+        Self {
+            id,
+            ..Self::default()
+        }
+    }
+
+    fn decode_opcodes(
+        &mut self,
+        reader: &mut Cursor<&[u8]>,
+        _layout: Layout,
+    ) -> io::Result<Option<u8>> {
+        decode_opcodes(self, reader)
+    }
+}
+
+fn decode_opcodes(
+    varbit_def: &mut VarbitDefinition,
+    reader: &mut Cursor<&[u8]>,
+) -> io::Result<Option<u8>> {
+    loop {
+        let opcode = reader.read_u8()?;
+
+        match opcode {
+            0 => break,
+            1 => {
+                varbit_def.varp_index = reader.read_u16()?;
+                varbit_def.least_significant_bit = reader.read_u8()?;
+                varbit_def.most_significant_bit = reader.read_u8()?;
+            }
+            opcode => return Ok(Some(opcode)),
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,177 @@
+use std::{io, io::Cursor};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::Definition;
+use crate::extension::ReadExt;
+
+/// Contains all the information about a certain world map area fetched from the cache through
+/// the [WorldMapLoader](../../loader/osrs/struct.WorldMapLoader.html).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct WorldMapDefinition {
+    pub id: u16,
+    pub internal_name: String,
+    pub name: String,
+    pub origin: Option<(u16, u16, u8)>,
+    pub background_color: i32,
+    pub main: bool,
+    pub zoom: u8,
+    pub sections: Vec<WorldMapSection>,
+}
+
+/// Maps a part of the game world onto the world map.
+///
+/// Regions are 64x64 tiles and are made up of 8x8 chunks, the bounds are inclusive.
+/// `old` is the location in the game world and `new` the location on the world map.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct WorldMapSection {
+    pub section_type: u8,
+    pub plane: u8,
+    pub planes: u8,
+    pub old: WorldMapBounds,
+    pub new: WorldMapBounds,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct WorldMapBounds {
+    pub region_x: (u16, u16),
+    pub region_y: (u16, u16),
+    pub chunk_x: (u8, u8),
+    pub chunk_y: (u8, u8),
+}
+
+impl Definition for WorldMapDefinition {
+    fn new(id: u16, buffer: &[u8]) -> crate::Result<Self> {
+        let mut reader = Cursor::new(buffer);
+        let world_map_def = decode_buffer(id, &mut reader)?;
+
+        Ok(world_map_def)
+    }
+}
+
+fn decode_buffer(id: u16, reader: &mut Cursor<&[u8]>) -> io::Result<WorldMapDefinition> {
+    let mut world_map_def = WorldMapDefinition {
+        id,
+        internal_name: reader.read_string()?,
+        name: reader.read_string()?,
+        ..WorldMapDefinition::default()
+    };
+
+    let origin = reader.read_i32()?;
+    if origin != -1 {
+        world_map_def.origin = Some((
+            (origin >> 14 & 0x3FFF) as u16,
+            (origin & 0x3FFF) as u16,
+            (origin >> 28 & 0x3) as u8,
+        ));
+    }
+
+    world_map_def.background_color = reader.read_i32()?;
+    let _ = reader.read_u8()?;
+    world_map_def.main = reader.read_u8()? == 1;
+    world_map_def.zoom = reader.read_u8()?;
+
+    let len = reader.read_u8()?;
+    for _ in 0..len {
+        world_map_def.sections.push(decode_section(reader)?);
+    }
+
+    Ok(world_map_def)
+}
+
+fn decode_section(reader: &mut Cursor<&[u8]>) -> io::Result<WorldMapSection> {
+    let section_type = reader.read_u8()?;
+    let plane = reader.read_u8()?;
+    let planes = reader.read_u8()?;
+
+    let (old, new) = match section_type {
+        0 => {
+            let old = decode_region_range(reader)?;
+            (old, decode_region_range(reader)?)
+        }
+        1 => {
+            let old = decode_region(reader)?;
+            (old, decode_region(reader)?)
+        }
+        2 => {
+            let old = decode_chunk_range(reader)?;
+            (old, decode_chunk_range(reader)?)
+        }
+        3 => {
+            let old = decode_chunk(reader)?;
+            (old, decode_chunk(reader)?)
+        }
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown world map section type {}", section_type),
+            ))
+        }
+    };
+
+    Ok(WorldMapSection {
+        section_type,
+        plane,
+        planes,
+        old,
+        new,
+    })
+}
+
+fn decode_region_range(reader: &mut Cursor<&[u8]>) -> io::Result<WorldMapBounds> {
+    let x_low = reader.read_u16()?;
+    let y_low = reader.read_u16()?;
+    let x_high = reader.read_u16()?;
+    let y_high = reader.read_u16()?;
+
+    Ok(WorldMapBounds {
+        region_x: (x_low, x_high),
+        region_y: (y_low, y_high),
+        chunk_x: (0, 7),
+        chunk_y: (0, 7),
+    })
+}
+
+fn decode_region(reader: &mut Cursor<&[u8]>) -> io::Result<WorldMapBounds> {
+    let x = reader.read_u16()?;
+    let y = reader.read_u16()?;
+
+    Ok(WorldMapBounds {
+        region_x: (x, x),
+        region_y: (y, y),
+        chunk_x: (0, 7),
+        chunk_y: (0, 7),
+    })
+}
+
+fn decode_chunk_range(reader: &mut Cursor<&[u8]>) -> io::Result<WorldMapBounds> {
+    let x = reader.read_u16()?;
+    let chunk_x = (reader.read_u8()?, reader.read_u8()?);
+    let y = reader.read_u16()?;
+    let chunk_y = (reader.read_u8()?, reader.read_u8()?);
+
+    Ok(WorldMapBounds {
+        region_x: (x, x),
+        region_y: (y, y),
+        chunk_x,
+        chunk_y,
+    })
+}
+
+fn decode_chunk(reader: &mut Cursor<&[u8]>) -> io::Result<WorldMapBounds> {
+    let x = reader.read_u16()?;
+    let y = reader.read_u16()?;
+    let chunk_x = reader.read_u8()?;
+    let chunk_y = reader.read_u8()?;
+
+    Ok(WorldMapBounds {
+        region_x: (x, x),
+        region_y: (y, y),
+        chunk_x: (chunk_x, chunk_x),
+        chunk_y: (chunk_y, chunk_y),
+    })
+}
@@ -1,7 +1,9 @@
 #[allow(clippy::too_many_lines)]
 mod item_def;
+mod sprite_def;
 
 pub use item_def::*;
+pub use sprite_def::*;
 
 use crate::Cache;
 use runefs::{IndexMetadata, ArchiveFileGroup, REFERENCE_TABLE_ID};
@@ -12,6 +14,15 @@ pub(crate) const ID_BLOCK_SIZE: usize = 256;
 /// Marker trait for definitions.
 pub trait Definition: Sized {
     fn new(id: u32, buffer: &[u8]) -> crate::Result<Self>;
+
+    /// Same as [`new`](Definition::new) but also asserts that the whole buffer was consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReadError::TrailingBytes` if any bytes remain after decoding.
+    fn new_strict(id: u32, buffer: &[u8]) -> crate::Result<Self> {
+        Self::new(id, buffer)
+    }
 }
 
 /// Adds definition fetching from the cache to every struct that implements `Definition`.
@@ -21,6 +32,8 @@ pub trait FetchDefinition: Definition {
     ///
     /// Note: every archive contains only one definition. (1:1)
     ///
+    /// Archives and archive files with a length of zero are skipped instead of decoded.
+    ///
     /// # Errors
     ///
     /// Can return multiple errors: if reading, decoding or parsing definition buffers fail.
@@ -28,23 +41,34 @@ pub trait FetchDefinition: Definition {
     where
         D: Definition,
     {
-        let buffer = cache.read(REFERENCE_TABLE_ID, index_id as u32)?.decode()?;
+        let buffer = cache.decode(cache.read(REFERENCE_TABLE_ID, index_id as u32)?)?;
         let archives = IndexMetadata::from_buffer(buffer)?;
 
         let mut definitions = std::collections::HashMap::new();
-        let mut base_id = 0;
 
-        for archive in &archives {
-            let buffer = cache.read(index_id, archive.id as u32)?.decode()?;
+        for (block, archive) in archives.iter().enumerate() {
+            let base_id = block * ID_BLOCK_SIZE;
+
+            let buffer = cache.read(index_id, archive.id as u32)?;
+            if buffer.is_empty() {
+                continue;
+            }
+
+            let buffer = cache.decode(buffer)?;
+            if buffer.is_empty() {
+                continue;
+            }
 
             let archive_group = ArchiveFileGroup::from_buffer(&buffer, archive.entry_count);
 
             for archive_file in archive_group {
+                if archive_file.data.is_empty() {
+                    continue;
+                }
+
                 let id = base_id + archive.valid_ids[archive_file.id as usize] as usize;
                 definitions.insert(id as u32, D::new(id as u32, &archive_file.data)?);
             }
-
-            base_id += ID_BLOCK_SIZE;
         }
 
         Ok(definitions)
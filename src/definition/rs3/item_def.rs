@@ -1,10 +1,13 @@
-use std::{io, io::BufReader};
+use std::{collections::BTreeMap, io, io::Cursor};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use super::Definition;
-use crate::{extension::ReadExt, util};
+use crate::{error::ReadError, extension::ReadExt, util};
+
+/// The equip slot of the shield, two-handed weapons hide it.
+const SHIELD_SLOT: u8 = 5;
 
 /// Contains all the information about a certain item fetched from the cache through
 /// the [ItemLoader](../../loader/rs3/struct.ItemLoader.html).
@@ -34,6 +37,12 @@ pub struct ItemDefinition {
     pub lent: bool,
     pub bind_link: Option<u16>,
     pub bind_tempalte: Option<u16>,
+    pub category: Option<u16>,
+    /// The mouse cursors of opcodes 127 to 130, as the option slot and the cursor id.
+    ///
+    /// The first two are for ground options, the last two for interface options.
+    pub cursors: [Option<(u8, u16)>; 4],
+    pub params: BTreeMap<u32, String>,
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -55,18 +64,47 @@ pub struct ModelData {
     pub modified_colors: Vec<u16>,
     pub original_texture_colors: Vec<u16>,
     pub modified_texture_colors: Vec<u16>,
+    pub rotation3: u16,
+    /// 128 when the item doesn't set it.
+    pub resize_x: u16,
+    /// 128 when the item doesn't set it.
+    pub resize_y: u16,
+    /// 128 when the item doesn't set it.
+    pub resize_z: u16,
+    pub ambient: i8,
+    pub contrast: i8,
+    pub male_head_model1: Option<u32>,
+    pub female_head_model1: Option<u32>,
+    pub male_head_model2: Option<u32>,
+    pub female_head_model2: Option<u32>,
+}
+
+impl ItemDefinition {
+    /// Checks if the item is wielded with both hands, i.e. it hides the shield slot.
+    pub fn two_handed(&self) -> bool {
+        self.equip_hide_slot1 == SHIELD_SLOT
+    }
 }
 
 impl Definition for ItemDefinition {
     fn new(id: u32, buffer: &[u8]) -> crate::Result<Self> {
-        let mut reader = BufReader::new(buffer);
+        let mut reader = Cursor::new(buffer);
+        let item_def = decode_buffer(id, &mut reader)?;
+
+        Ok(item_def)
+    }
+
+    fn new_strict(id: u32, buffer: &[u8]) -> crate::Result<Self> {
+        let mut reader = Cursor::new(buffer);
         let item_def = decode_buffer(id, &mut reader)?;
+        util::ensure_consumed(&reader)?;
 
         Ok(item_def)
     }
 }
 
-fn decode_buffer(id: u32, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefinition> {
+fn decode_buffer(id: u32, reader: &mut Cursor<&[u8]>) -> io::Result<ItemDefinition> {
+    let len = util::remaining(reader);
     let mut item_def = ItemDefinition {
         id,
         options: [
@@ -83,6 +121,12 @@ fn decode_buffer(id: u32, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
             "".to_string(),
             "Drop".to_string(),
         ],
+        model_data: ModelData {
+            resize_x: 128,
+            resize_y: 128,
+            resize_z: 128,
+            ..ModelData::default()
+        },
         ..ItemDefinition::default()
     };
 
@@ -156,7 +200,7 @@ fn decode_buffer(id: u32, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
             41 => {
                 let len = reader.read_u8()? as usize;
                 item_def.model_data.original_texture_colors = Vec::with_capacity(len);
-                item_def.model_data.original_texture_colors = Vec::with_capacity(len);
+                item_def.model_data.modified_texture_colors = Vec::with_capacity(len);
                 for _ in 0..len {
                     item_def
                         .model_data
@@ -164,7 +208,7 @@ fn decode_buffer(id: u32, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
                         .push(reader.read_u16()?);
                     item_def
                         .model_data
-                        .original_texture_colors
+                        .modified_texture_colors
                         .push(reader.read_u16()?);
                 }
             }
@@ -190,22 +234,22 @@ fn decode_buffer(id: u32, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
                 item_def.noted_template = Some(reader.read_u16()?);
                 item_def.stackable = true;
             }
+            90 => item_def.model_data.male_head_model1 = Some(reader.read_smart()?),
+            91 => item_def.model_data.female_head_model1 = Some(reader.read_smart()?),
+            92 => item_def.model_data.male_head_model2 = Some(reader.read_smart()?),
+            93 => item_def.model_data.female_head_model2 = Some(reader.read_smart()?),
+            94 => item_def.category = Some(reader.read_u16()?),
+            95 => item_def.model_data.rotation3 = reader.read_u16()?,
             100..=109 => {
-                item_def.stack_ids = Some([0; 10]);
-                item_def.stack_count = Some([0; 10]);
-                match item_def.stack_ids {
-                    Some(mut stack_ids) => {
-                        stack_ids[opcode as usize - 100] = reader.read_u16()?;
-                    }
-                    _ => unreachable!(),
-                }
-                match item_def.stack_count {
-                    Some(mut stack_count) => {
-                        stack_count[opcode as usize - 100] = reader.read_u16()?;
-                    }
-                    _ => unreachable!(),
-                }
-            }
+                let slot = opcode as usize - 100;
+                item_def.stack_ids.get_or_insert([0; 10])[slot] = reader.read_u16()?;
+                item_def.stack_count.get_or_insert([0; 10])[slot] = reader.read_u16()?;
+            }
+            110 => item_def.model_data.resize_x = reader.read_u16()?,
+            111 => item_def.model_data.resize_y = reader.read_u16()?,
+            112 => item_def.model_data.resize_z = reader.read_u16()?,
+            113 => item_def.model_data.ambient = reader.read_i8()?,
+            114 => item_def.model_data.contrast = reader.read_i8()?,
             115 => {
                 item_def.team = reader.read_u8()?;
             }
@@ -223,8 +267,8 @@ fn decode_buffer(id: u32, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
                 reader.read_u8()?;
             }
             127..=130 => {
-                reader.read_u8()?;
-                reader.read_u16()?;
+                let option = reader.read_u8()?;
+                item_def.cursors[opcode as usize - 127] = Some((option, reader.read_u16()?));
             }
             132 => {
                 let len = reader.read_u8()?;
@@ -250,21 +294,22 @@ fn decode_buffer(id: u32, reader: &mut BufReader<&[u8]>) -> io::Result<ItemDefin
                 }
             }
             249 => {
-                util::read_parameters(reader)?;
+                item_def.params = util::read_parameters(reader)?.into_iter().collect();
             }
             15 | 156 | 157 | 165 | 167 => {}
-            96 | 113 | 114 | 134 => {
+            96 | 134 => {
                 reader.read_u8()?;
             }
-            18 | 44 | 45 | 94 | 95 | 110..=112 | 142..=146 | 150..=154 | 161..=163 => {
+            18 | 44 | 45 | 142..=146 | 150..=154 | 161..=163 => {
                 reader.read_u16()?;
             }
-            90..=93 | 242..=248 => {
+            242..=248 => {
                 reader.read_smart()?;
             }
-            _ => {
-                println!("{} {}", id, opcode);
-                unreachable!()
+            // The payload size of an unknown opcode is unknown, decoding can't continue.
+            opcode => {
+                let offset = len - util::remaining(reader) - 1;
+                return Err(ReadError::UnknownOpcode { opcode, offset }.into());
             }
         }
     }
@@ -0,0 +1,87 @@
+use std::io;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::Definition;
+use crate::{
+    definition::osrs::{self, SpriteFrame},
+    extension::ReadExt,
+};
+
+/// Contains all the frames of a certain sprite fetched from the cache through
+/// the [SpriteLoader](../../loader/rs3/struct.SpriteLoader.html).
+///
+/// RS3 stores sprites either in the paletted format that OSRS uses or in a newer
+/// true color format, which is flagged by the highest bit of the trailing frame
+/// count. True color sprites consist of a single frame.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct SpriteDefinition {
+    pub id: u32,
+    pub max_width: u16,
+    pub max_height: u16,
+    pub frames: Vec<SpriteFrame>,
+}
+
+impl SpriteDefinition {
+    /// The amount of frames in this sprite, more than one for animated sprites.
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+impl Definition for SpriteDefinition {
+    fn new(id: u32, buffer: &[u8]) -> crate::Result<Self> {
+        let trailer = buffer
+            .get(buffer.len().saturating_sub(2)..)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?
+            .read_u16()?;
+
+        if trailer & 0x8000 == 0 {
+            let sprite_def = <osrs::SpriteDefinition as osrs::Definition>::new(0, buffer)?;
+
+            return Ok(Self {
+                id,
+                max_width: sprite_def.max_width,
+                max_height: sprite_def.max_height,
+                frames: sprite_def.frames,
+            });
+        }
+
+        let sprite_def = decode_true_color(id, buffer)?;
+
+        Ok(sprite_def)
+    }
+}
+
+fn decode_true_color(id: u32, mut buffer: &[u8]) -> io::Result<SpriteDefinition> {
+    let _format = buffer.read_u8()?;
+    let has_alpha = buffer.read_u8()? & 1 != 0;
+    let width = buffer.read_u16()?;
+    let height = buffer.read_u16()?;
+
+    let len = width as usize * height as usize;
+    let mut pixels = Vec::with_capacity(len);
+    for _ in 0..len {
+        pixels.push(0xFF00_0000 | buffer.read_u24()?);
+    }
+    if has_alpha {
+        for pixel in &mut pixels {
+            *pixel = (*pixel & 0x00FF_FFFF) | (buffer.read_u8()? as u32) << 24;
+        }
+    }
+
+    Ok(SpriteDefinition {
+        id,
+        max_width: width,
+        max_height: height,
+        frames: vec![SpriteFrame {
+            offset_x: 0,
+            offset_y: 0,
+            width,
+            height,
+            pixels,
+        }],
+    })
+}
@@ -11,7 +11,7 @@ pub(crate) type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     /// Wrapper for the std::io::Error type.
     #[error(transparent)]
-    Io(#[from] io::Error),
+    Io(io::Error),
     #[error(transparent)]
     NameHash(#[from] NameHashMismatch),
     #[error("unknown parser error")]
@@ -19,7 +19,13 @@ pub enum Error {
     #[error(transparent)]
     Validate(#[from] ValidateError),
     #[error(transparent)]
+    Read(#[from] ReadError),
+    #[error(transparent)]
     RuneFs(#[from] RuneFsError),
+    #[cfg(feature = "bincode")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bincode")))]
+    #[error(transparent)]
+    Bincode(#[from] bincode::Error),
 }
 
 #[derive(Error, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -44,3 +50,52 @@ pub enum ValidateError {
         external: u32,
     },
 }
+
+#[derive(Error, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ReadError {
+    #[error("definition buffer has {remaining} trailing bytes after the terminating opcode")]
+    TrailingBytes { remaining: usize },
+    #[error("parameter {entry} of the parameter block is truncated or has an invalid type")]
+    MalformedParams { entry: usize },
+    #[error("archive id {id} doesn't fit in a u16")]
+    ArchiveIdOutOfRange { id: u32 },
+    #[error("unknown opcode {opcode} at offset {offset}")]
+    UnknownOpcode { opcode: u8, offset: usize },
+    #[error("index file {index_id} is {len} bytes long, which is not a multiple of the 6 byte entry size")]
+    InvalidIndexFile { index_id: u8, len: u64 },
+    #[error("reference table protocol {protocol} is not supported")]
+    UnsupportedProtocol { protocol: u8 },
+    #[error("sprite {id} is missing or has no frames")]
+    SpriteNotFound { id: u16 },
+}
+
+/// A problem that was recovered from while decoding leniently.
+#[derive(Error, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum DecodeWarning {
+    /// The payload size of an unknown opcode is unknown, so the opcodes after it were skipped.
+    #[error("unknown opcode {opcode} at offset {offset}, the remaining opcodes were skipped")]
+    UnknownOpcode { opcode: u8, offset: usize },
+    /// Decoding failed part way, the fields decoded up to that point were kept.
+    #[error("decoding stopped early: {0}")]
+    Stopped(String),
+}
+
+impl From<ReadError> for io::Error {
+    fn from(error: ReadError) -> Self {
+        Self::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+impl From<io::Error> for Error {
+    /// Read errors raised inside decoders are unwrapped so they surface as `Error::Read`.
+    fn from(error: io::Error) -> Self {
+        let read_error = error
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<ReadError>());
+
+        match read_error {
+            Some(read_error) => Self::Read(*read_error),
+            None => Self::Io(error),
+        }
+    }
+}
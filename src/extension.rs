@@ -1,6 +1,32 @@
 //! Extension traits.
 
-use std::io::{self, Read};
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+};
+
+/// The payload size of an opcode, used by [`ReadExt::read_until_opcode_zero`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum PayloadSize {
+    /// A fixed number of bytes.
+    Fixed(usize),
+    /// A null-terminated string.
+    String,
+    /// A `u8` count followed by that many elements of the given size.
+    Counted(usize),
+    /// A parameter table, a `u8` count followed by that many entries of a type byte,
+    /// a `u24` key and a string or `i32` value.
+    Params,
+}
+
+/// The characters of the `0x80..0xA0` range in the Windows-1252 encoding used by the cache,
+/// the unassigned code points are decoded as `?`.
+const CP1252_HIGH: [char; 32] = [
+    '\u{20AC}', '?', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '?', '\u{017D}', '?', '?',
+    '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}', '\u{02DC}',
+    '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '?', '\u{017E}', '\u{0178}',
+];
 
 /// Adds easy byte reading onto a [`Read`] instance.
 ///
@@ -11,8 +37,10 @@ pub trait ReadExt: Read {
     fn read_u16(&mut self) -> io::Result<u16>;
     fn read_i16(&mut self) -> io::Result<i16>;
     fn read_smart_u16(&mut self) -> io::Result<u16>;
+    fn read_smart_i16(&mut self) -> io::Result<i16>;
     fn read_u24(&mut self) -> io::Result<u32>;
     fn read_i24(&mut self) -> io::Result<i32>;
+    fn read_rgb(&mut self) -> io::Result<(u8, u8, u8)>;
     fn read_u32(&mut self) -> io::Result<u32>;
     fn read_i32(&mut self) -> io::Result<i32>;
     fn read_u64(&mut self) -> io::Result<u64>;
@@ -20,7 +48,28 @@ pub trait ReadExt: Read {
     fn read_u128(&mut self) -> io::Result<u128>;
     fn read_i128(&mut self) -> io::Result<i128>;
     fn read_smart(&mut self) -> io::Result<u32>;
+    /// Reads a null-terminated string.
+    ///
+    /// Cache strings are encoded as Windows-1252, which is Latin-1 with printable
+    /// characters such as `€` and `™` in the `0x80..0xA0` range.
     fn read_string(&mut self) -> io::Result<String>;
+    fn read_bytes(&mut self, n: usize) -> io::Result<Vec<u8>>;
+    fn read_bytes_into(&mut self, buffer: &mut [u8]) -> io::Result<()>;
+    /// Advances past `n` bytes without allocating a buffer for them.
+    ///
+    /// Fails with `UnexpectedEof` if fewer than `n` bytes remain.
+    fn skip(&mut self, n: usize) -> io::Result<()>;
+    /// Advances past a null-terminated string without decoding it.
+    fn skip_string(&mut self) -> io::Result<()>;
+    /// Splits a definition into its raw opcodes and payloads, stopping at opcode 0.
+    ///
+    /// `sizes` tells how large the payload of every opcode is. An opcode missing from
+    /// `sizes` ends the stream, it is returned with all remaining bytes as its payload.
+    /// Useful for reverse-engineering new definition formats.
+    fn read_until_opcode_zero(
+        &mut self,
+        sizes: &HashMap<u8, PayloadSize>,
+    ) -> io::Result<Vec<(u8, Vec<u8>)>>;
 }
 
 impl<T: Read> ReadExt for T {
@@ -61,6 +110,17 @@ impl<T: Read> ReadExt for T {
             Ok(value - 0xC000)
         }
     }
+    fn read_smart_i16(&mut self) -> io::Result<i16> {
+        let byte = self.read_u8()?;
+
+        if byte < 128 {
+            Ok(byte as i16 - 64)
+        } else {
+            let value = u16::from_be_bytes([byte, self.read_u8()?]);
+            Ok((value as i32 - 0xC000) as i16)
+        }
+    }
+
     fn read_u24(&mut self) -> io::Result<u32> {
         let mut buffer = [0; 3];
         self.read_exact(&mut buffer)?;
@@ -72,6 +132,12 @@ impl<T: Read> ReadExt for T {
         Ok(self.read_u24()? as i32)
     }
 
+    fn read_rgb(&mut self) -> io::Result<(u8, u8, u8)> {
+        let rgb = self.read_u24()?;
+
+        Ok(((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+    }
+
     fn read_u32(&mut self) -> io::Result<u32> {
         let mut buffer = [0; 4];
         self.read_exact(&mut buffer)?;
@@ -141,6 +207,102 @@ impl<T: Read> ReadExt for T {
                 break;
             }
         }
-        Ok(String::from_utf8_lossy(&bytes[..]).to_string())
+        Ok(bytes
+            .into_iter()
+            .map(|byte| match byte {
+                0x80..=0x9F => CP1252_HIGH[byte as usize - 0x80],
+                _ => char::from(byte),
+            })
+            .collect())
+    }
+
+    fn read_bytes(&mut self, n: usize) -> io::Result<Vec<u8>> {
+        let mut buffer = vec![0; n];
+        self.read_exact(&mut buffer)?;
+
+        Ok(buffer)
+    }
+
+    fn read_bytes_into(&mut self, buffer: &mut [u8]) -> io::Result<()> {
+        self.read_exact(buffer)
+    }
+
+    fn skip(&mut self, n: usize) -> io::Result<()> {
+        let skipped = io::copy(&mut self.take(n as u64), &mut io::sink())?;
+        if skipped < n as u64 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        Ok(())
+    }
+
+    fn skip_string(&mut self) -> io::Result<()> {
+        while self.read_u8()? != 0 {}
+
+        Ok(())
+    }
+
+    fn read_until_opcode_zero(
+        &mut self,
+        sizes: &HashMap<u8, PayloadSize>,
+    ) -> io::Result<Vec<(u8, Vec<u8>)>> {
+        let mut opcodes = Vec::new();
+
+        loop {
+            let opcode = self.read_u8()?;
+            if opcode == 0 {
+                break;
+            }
+
+            let payload = match sizes.get(&opcode) {
+                Some(PayloadSize::Fixed(len)) => self.read_bytes(*len)?,
+                Some(PayloadSize::String) => {
+                    let mut payload = Vec::new();
+                    read_string_bytes(self, &mut payload)?;
+                    payload
+                }
+                Some(PayloadSize::Counted(len)) => {
+                    let count = self.read_u8()?;
+                    let mut payload = vec![count];
+                    payload.extend(self.read_bytes(count as usize * len)?);
+                    payload
+                }
+                Some(PayloadSize::Params) => {
+                    let count = self.read_u8()?;
+                    let mut payload = vec![count];
+                    for _ in 0..count {
+                        let is_string = self.read_u8()?;
+                        payload.push(is_string);
+                        payload.extend(self.read_bytes(3)?);
+                        if is_string == 1 {
+                            read_string_bytes(self, &mut payload)?;
+                        } else {
+                            payload.extend(self.read_bytes(4)?);
+                        }
+                    }
+                    payload
+                }
+                None => {
+                    let mut payload = Vec::new();
+                    self.read_to_end(&mut payload)?;
+                    opcodes.push((opcode, payload));
+                    break;
+                }
+            };
+            opcodes.push((opcode, payload));
+        }
+
+        Ok(opcodes)
+    }
+}
+
+/// Appends a null-terminated string to `payload`, terminator included.
+fn read_string_bytes<R: Read>(reader: &mut R, payload: &mut Vec<u8>) -> io::Result<()> {
+    loop {
+        let byte = reader.read_u8()?;
+        payload.push(byte);
+        if byte == 0 {
+            return Ok(());
+        }
     }
 }
@@ -0,0 +1,319 @@
+//! Handles for reading from a single index.
+
+use std::{fs, path::Path};
+
+use runefs::{
+    codec::{Buffer, Decoded, Encoded},
+    ArchiveRef, ARCHIVE_REF_LEN, IDX_PREFIX, REFERENCE_TABLE_ID,
+};
+
+use crate::{error::ReadError, extension::ReadExt, Cache};
+
+/// The id of an archive within an index.
+///
+/// Archive ids are `u32` throughout the read API, but OSRS definitions are keyed by `u16`.
+/// Widening is always possible with `From`, narrowing has to go through `TryFrom` so ids
+/// above `u16::MAX` can't be truncated by accident.
+///
+/// # Examples
+///
+/// ```
+/// use rscache::index::ArchiveId;
+///
+/// let id = ArchiveId::from(70_000_u32);
+///
+/// assert!(id.uses_expanded_header());
+/// assert_eq!(u32::from(id), 70_000);
+/// assert!(u16::try_from(id).is_err());
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ArchiveId(pub u32);
+
+impl ArchiveId {
+    /// Whether the sectors of this archive use the expanded header with a 4 byte id.
+    #[inline]
+    pub const fn uses_expanded_header(self) -> bool {
+        self.0 > u16::MAX as u32
+    }
+}
+
+impl From<u16> for ArchiveId {
+    #[inline]
+    fn from(id: u16) -> Self {
+        Self(u32::from(id))
+    }
+}
+
+impl From<u32> for ArchiveId {
+    #[inline]
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl From<ArchiveId> for u32 {
+    #[inline]
+    fn from(id: ArchiveId) -> Self {
+        id.0
+    }
+}
+
+impl TryFrom<ArchiveId> for u16 {
+    type Error = ReadError;
+
+    #[inline]
+    fn try_from(id: ArchiveId) -> Result<Self, Self::Error> {
+        Self::try_from(id.0).map_err(|_| ReadError::ArchiveIdOutOfRange { id: id.0 })
+    }
+}
+
+/// Checks that every `.idx` file in the cache directory is made of whole entries.
+///
+/// An index file is a flat list of 6 byte entries, a 3 byte archive length followed by
+/// the 3 byte sector the archive starts at. The entry of archive `n` is at offset `n * 6`.
+/// A file with a partial entry at the end is truncated or corrupt, and would otherwise
+/// silently lose its last entry.
+///
+/// # Errors
+///
+/// Returns `ReadError::InvalidIndexFile` for the first index file with a length that isn't
+/// a multiple of 6.
+pub(crate) fn validate_index_files(path: &Path) -> crate::Result<()> {
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let index_id = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix(IDX_PREFIX))
+            .and_then(|id| id.parse::<u8>().ok());
+
+        if let Some(index_id) = index_id {
+            let len = entry.metadata()?.len();
+            if len % ARCHIVE_REF_LEN as u64 != 0 {
+                return Err(ReadError::InvalidIndexFile { index_id, len }.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A handle to one index of a [`Cache`], obtained through [`Cache::index`].
+///
+/// The index and its reference table are looked up once when the handle is made,
+/// every read through the handle goes straight to the archive.
+///
+/// # Examples
+///
+/// ```
+/// # use rscache::Cache;
+/// # fn main() -> Result<(), rscache::Error> {
+/// # let cache = Cache::new("./data/osrs_cache")?;
+/// let config = cache.index(2)?;
+///
+/// for archive_id in config.archive_ids().take(3) {
+///     let buffer = config.read(archive_id)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Index<'cache> {
+    cache: &'cache Cache,
+    id: u8,
+    index: &'cache runefs::Index,
+}
+
+impl<'cache> Index<'cache> {
+    pub(crate) fn new(cache: &'cache Cache, id: u8, index: &'cache runefs::Index) -> Self {
+        Self { cache, id, index }
+    }
+
+    #[inline]
+    pub const fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Retrieves the data of an archive in this index.
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`Cache::read`].
+    pub fn read(&self, archive_id: u32) -> crate::Result<Buffer<Encoded>> {
+        self.cache.read(self.id, archive_id)
+    }
+
+    /// Retrieves and decodes the data of an archive in this index.
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`Cache::read_decoded`].
+    pub fn read_decoded(&self, archive_id: u32) -> crate::Result<Buffer<Decoded>> {
+        self.cache.read_decoded(self.id, archive_id)
+    }
+
+    /// The location of an archive in the data file, for use with
+    /// [`Cache::read_archive_struct`].
+    pub fn archive_ref(&self, archive_id: u32) -> Option<&'cache ArchiveRef> {
+        self.index.archive_refs.get(&archive_id)
+    }
+
+    /// The ids of all archives listed in the reference table of this index.
+    pub fn archive_ids(&self) -> impl Iterator<Item = u32> + 'cache {
+        self.index.metadata.iter().map(|archive| archive.id)
+    }
+
+    /// Parses the header fields and archive lengths of the reference table of this index.
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`ReferenceTable::from_buffer`].
+    pub fn reference_table(&self) -> crate::Result<ReferenceTable> {
+        let buffer = self
+            .cache
+            .read_decoded(REFERENCE_TABLE_ID, u32::from(self.id))?;
+
+        ReferenceTable::from_buffer(&buffer)
+    }
+
+    /// Checks if the reference table of this index lists the archive.
+    ///
+    /// When an overlay is used the archive exists if either cache lists it.
+    pub fn exists(&self, archive_id: u32) -> bool {
+        // Archive ids are delta encoded in the reference table so they are always sorted.
+        let listed = self
+            .index
+            .metadata
+            .iter()
+            .as_slice()
+            .binary_search_by_key(&archive_id, |archive| archive.id)
+            .is_ok();
+
+        listed
+            || self
+                .cache
+                .overlay
+                .as_ref()
+                .is_some_and(|overlay| overlay.archive_exists(self.id, archive_id))
+    }
+}
+
+/// The header, checksums and lengths of a reference table.
+///
+/// Archive versions and the file lists that follow the lengths aren't parsed, they are in
+/// the archive metadata of the index.
+///
+/// OSRS writes protocol 5 and 6 tables, RS3 writes protocol 6 and 7 tables. From protocol 6
+/// onwards the table has a version, protocol 7 stores archive ids and counts as big smarts.
+/// The flags byte decides which per archive fields are present, absent fields are `None`.
+///
+/// Per archive fields are in the order of `archive_ids`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ReferenceTable {
+    pub protocol: u8,
+    pub version: Option<u32>,
+    pub archive_ids: Vec<u32>,
+    pub name_hashes: Option<Vec<i32>>,
+    pub crcs: Vec<u32>,
+    /// A second checksum over the decompressed archive.
+    pub uncompressed_crcs: Option<Vec<u32>>,
+    pub whirlpool: Option<Vec<[u8; 64]>>,
+    /// The lengths of every archive, empty unless the table is sized.
+    pub lengths: Vec<ArchiveLengths>,
+}
+
+/// The compressed and decompressed length of an archive, see [`ReferenceTable`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ArchiveLengths {
+    pub archive_id: u32,
+    pub compressed: u32,
+    pub decompressed: u32,
+}
+
+impl ReferenceTable {
+    /// Parses a decoded reference table of protocol 5, 6 or 7.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReadError::UnsupportedProtocol` for other protocols, or an I/O error if the
+    /// buffer is truncated.
+    pub fn from_buffer(mut buffer: &[u8]) -> crate::Result<Self> {
+        let reader = &mut buffer;
+        let protocol = reader.read_u8()?;
+        if !(5..=7).contains(&protocol) {
+            return Err(ReadError::UnsupportedProtocol { protocol }.into());
+        }
+
+        let version = if protocol >= 6 {
+            Some(reader.read_u32()?)
+        } else {
+            None
+        };
+        let flags = reader.read_u8()?;
+        let named = flags & 1 != 0;
+        let whirlpool = flags & 2 != 0;
+        let sized = flags & 4 != 0;
+        let hashed = flags & 8 != 0;
+
+        let read_count = |reader: &mut &[u8]| -> std::io::Result<u32> {
+            if protocol >= 7 {
+                reader.read_smart()
+            } else {
+                reader.read_u16().map(u32::from)
+            }
+        };
+
+        let archive_count = read_count(reader)? as usize;
+        let mut archive_ids = Vec::with_capacity(archive_count);
+        let mut archive_id = 0_u32;
+        for _ in 0..archive_count {
+            archive_id = archive_id.wrapping_add(read_count(reader)?);
+            archive_ids.push(archive_id);
+        }
+
+        // Name hashes, crcs, uncompressed crcs and whirlpool digests, in that order.
+        let name_hashes = named
+            .then(|| (0..archive_count).map(|_| reader.read_i32()).collect())
+            .transpose()?;
+        let crcs = (0..archive_count)
+            .map(|_| reader.read_u32())
+            .collect::<std::io::Result<_>>()?;
+        let uncompressed_crcs = hashed
+            .then(|| (0..archive_count).map(|_| reader.read_u32()).collect())
+            .transpose()?;
+        let whirlpool = whirlpool
+            .then(|| {
+                (0..archive_count)
+                    .map(|_| {
+                        let mut digest = [0; 64];
+                        reader.read_bytes_into(&mut digest).map(|_| digest)
+                    })
+                    .collect()
+            })
+            .transpose()?;
+
+        let mut lengths = Vec::new();
+        if sized {
+            for &archive_id in &archive_ids {
+                lengths.push(ArchiveLengths {
+                    archive_id,
+                    compressed: reader.read_u32()?,
+                    decompressed: reader.read_u32()?,
+                });
+            }
+        }
+
+        let table = Self {
+            protocol,
+            version,
+            archive_ids,
+            name_hashes,
+            crcs,
+            uncompressed_crcs,
+            whirlpool,
+            lengths,
+        };
+
+        Ok(table)
+    }
+}
@@ -33,14 +33,20 @@
 //! system. The map will remain valid even after the `File` is dropped, it's
 //! completely independent of the `File` used to create it. Therefore, the use
 //! of unsafe is not propagated outwards. When the `Cache` is dropped memory
-//! will be subsequently unmapped.
+//! will be subsequently unmapped. A data file that is truncated while mapped
+//! isn't detected, see [`CacheBuilder::mmap`]. Caches opened with
+//! [`CacheBuilder::mmap`] set to `false` read the data file into memory instead.
 //!
 //! # Features
 //!
 //! The cache's protocol defaults to OSRS. In order to use the RS3 protocol you
 //! can enable the `rs3` feature flag. A lot of types derive [serde]'s
 //! `Serialize` and `Deserialize`. The `serde-derive` feature flag can be used
-//! to enable (de)serialization on any compatible types.
+//! to enable (de)serialization on any compatible types. The `bincode` feature
+//! flag adds compact binary snapshots to the loaders so decoded definitions can
+//! be restored without reading the cache. The `archive` feature flag allows
+//! opening a cache that is bundled as a `.tar.gz`. The `obj` feature flag
+//! exports decoded models to Wavefront OBJ.
 //!
 //! # Quick Start
 //!
@@ -57,15 +63,15 @@
 //! ```
 //! 
 //! If you want to share the instance over multiple threads you can do so by
-//! wrapping it in an
-//! [`Arc`](https://doc.rust-lang.org/std/sync/struct.Arc.html)
+//! cloning it. `Cache` keeps its internals behind an
+//! [`Arc`](https://doc.rust-lang.org/std/sync/struct.Arc.html) so every clone
+//! shares the same files without re-opening them.
 //! ```
 //! use rscache::Cache;
-//! use std::sync::Arc;
 //! 
-//! let cache = Arc::new(Cache::new("./data/osrs_cache").unwrap());
+//! let cache = Cache::new("./data/osrs_cache").unwrap();
 //!     
-//! let c = Arc::clone(&cache);
+//! let c = cache.clone();
 //! std::thread::spawn(move || {
 //!     c.read(0, 10).unwrap();
 //! });
@@ -78,7 +84,7 @@
 //! The recommended usage would be to wrap it using
 //! [`once_cell`](https://docs.rs/once_cell/latest/once_cell/) making it the
 //! easiest way to access cache data from anywhere and at any time. No need for
-//! an `Arc` or a `Mutex` because `Cache` will always be `Send` & `Sync`.
+//! a `Mutex` because `Cache` will always be `Send` & `Sync`.
 //! ```
 //! use rscache::Cache;
 //! use once_cell::sync::Lazy;
@@ -128,34 +134,95 @@
 #[macro_use]
 pub mod util;
 pub mod checksum;
+pub mod codec;
 pub mod definition;
 pub mod error;
 pub mod extension;
+pub mod index;
 pub mod loader;
+pub mod sector;
 
 #[doc(inline)]
 pub use error::Error;
 use error::Result;
 
-use checksum::Checksum;
+use checksum::{Checksum, Entry};
+use codec::{Container, Decompressor};
+use index::ArchiveId;
+use loader::{LoaderOptions, UnknownOpcodePolicy};
 #[cfg(feature = "rs3")]
 use checksum::{RsaChecksum, RsaKeys};
+use memmap2::Mmap;
+use nom::number::complete::{be_u32, be_u8};
 use runefs::codec::{Buffer, Decoded, Encoded};
 use runefs::error::{Error as RuneFsError, ReadError};
-use runefs::{ArchiveRef, Dat2, Indices, MAIN_DATA};
-use std::{io::Write, path::Path};
+use runefs::{ArchiveRef, Indices, MAIN_DATA, REFERENCE_TABLE_ID};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Write,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+};
+use util::{ArchiveCache, ArchiveCacheStats, CacheStats};
 
-/// A complete virtual representation of the RuneScape cache file system.
+/// The main data file, either memory-mapped or read into memory.
+///
+/// The length of a mapped file is only known when it is mapped. Nothing guards against the
+/// file being truncated afterwards, touching a page past its new end raises `SIGBUS` on
+/// Unix instead of returning an error.
 #[derive(Debug)]
+pub(crate) enum DataFile {
+    Mapped(Mmap),
+    Loaded(Vec<u8>),
+}
+
+impl DataFile {
+    fn open(path: &Path, mmap: bool) -> crate::Result<Self> {
+        if mmap {
+            // Safety: see the crate level docs, the data file is never written to.
+            Ok(Self::Mapped(unsafe { Mmap::map(&File::open(path)?)? }))
+        } else {
+            Ok(Self::Loaded(std::fs::read(path)?))
+        }
+    }
+}
+
+impl Deref for DataFile {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Mapped(map) => map,
+            Self::Loaded(data) => data,
+        }
+    }
+}
+
+/// A complete virtual representation of the RuneScape cache file system.
+///
+/// Cloning a `Cache` is cheap, all clones share the same memory map and indices.
+#[derive(Clone, Debug)]
 pub struct Cache {
-    pub(crate) data: Dat2,
-    pub(crate) indices: Indices,
+    pub(crate) data: Arc<DataFile>,
+    pub(crate) indices: Arc<Indices>,
+    pub(crate) decode_options: LoaderOptions,
+    pub(crate) overlay: Option<Arc<Cache>>,
+    pub(crate) archive_cache: Option<Arc<Mutex<ArchiveCache>>>,
+    pub(crate) decompressor: Option<Arc<dyn Decompressor>>,
+    /// Keeps the files extracted by `from_archive` alive for as long as the cache is.
+    #[cfg(feature = "archive")]
+    _extracted: Option<Arc<tempfile::TempDir>>,
 }
 
 impl Cache {
     /// Creates a high level virtual memory map over the cache directory.
     ///
     /// All files are isolated on allocation by keeping them as in-memory files.
+    /// The main data file is memory-mapped, so sector reads are slice operations
+    /// on the map rather than individual `read` syscalls. Use [`CacheBuilder::mmap`]
+    /// to read it into memory instead.
     ///
     /// # Errors
     ///
@@ -164,13 +231,140 @@ impl Cache {
     ///
     /// Other errors might include protocol changes in newer caches. Any error
     /// unrelated to I/O at this stage should be considered a bug.
+    ///
+    /// Index files with a length that isn't a multiple of the 6 byte entry size are
+    /// rejected with `ReadError::InvalidIndexFile`.
     pub fn new<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        Self::open(path.as_ref(), true)
+    }
+
+    fn open(path: &Path, mmap: bool) -> crate::Result<Self> {
+        index::validate_index_files(path)?;
+
+        let data = DataFile::open(&path.join(MAIN_DATA), mmap)?;
+
         Ok(Self {
-            data: Dat2::new(path.as_ref().join(MAIN_DATA))?,
-            indices: Indices::new(path)?,
+            data: Arc::new(data),
+            indices: Arc::new(Indices::new(path)?),
+            decode_options: LoaderOptions::default(),
+            overlay: None,
+            archive_cache: None,
+            decompressor: None,
+            #[cfg(feature = "archive")]
+            _extracted: None,
         })
     }
 
+    /// Starts configuring a cache, see [`CacheBuilder`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rscache::Cache;
+    /// # fn main() -> Result<(), rscache::Error> {
+    /// let cache = Cache::builder()
+    ///     .mmap(true)
+    ///     .cache_capacity(256)
+    ///     .open("./data/osrs_cache")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> CacheBuilder {
+        CacheBuilder::default()
+    }
+
+    /// Opens a cache that is bundled as a gzip compressed tarball.
+    ///
+    /// The tarball is extracted into a temporary directory which is removed once the
+    /// last clone of the cache is dropped. The cache files can either be at the root of
+    /// the tarball or inside a single top-level directory.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the tarball can't be extracted or if it doesn't contain a cache, see
+    /// [`new`](Cache::new) for the remaining errors.
+    #[cfg(feature = "archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "archive")))]
+    pub fn from_archive<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let file = std::fs::File::open(path)?;
+        tar::Archive::new(flate2::read::GzDecoder::new(file)).unpack(dir.path())?;
+
+        let mut cache_dir = dir.path().to_path_buf();
+        if !cache_dir.join(MAIN_DATA).exists() {
+            for entry in std::fs::read_dir(dir.path())? {
+                let path = entry?.path();
+                if path.join(MAIN_DATA).exists() {
+                    cache_dir = path;
+                    break;
+                }
+            }
+        }
+
+        Ok(Self {
+            _extracted: Some(Arc::new(dir)),
+            ..Self::new(cache_dir)?
+        })
+    }
+
+    /// Creates a cache over `base_path` with a second cache in `overlay_path` layered on top.
+    ///
+    /// Every read checks the overlay first and only falls back to the base cache when the
+    /// overlay doesn't contain the requested archive, or its entry for it is empty. This
+    /// makes it possible to test patches without mutating the base cache.
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`new`](Cache::new) for more details, both caches
+    /// have to be valid.
+    pub fn with_overlay<P: AsRef<Path>, O: AsRef<Path>>(
+        base_path: P,
+        overlay_path: O,
+    ) -> crate::Result<Self> {
+        Ok(Self {
+            overlay: Some(Arc::new(Self::new(overlay_path)?)),
+            ..Self::new(base_path)?
+        })
+    }
+
+    /// Enables the archive cache, keeping up to `capacity` decoded archives in memory.
+    ///
+    /// Only reads through [`read_decoded`](Cache::read_decoded) go through the archive cache,
+    /// the least recently used archive is evicted once the capacity is reached. All clones
+    /// made afterwards share the same archive cache.
+    pub fn with_archive_cache(mut self, capacity: usize) -> Self {
+        self.archive_cache = Some(Arc::new(Mutex::new(ArchiveCache::new(capacity))));
+        self
+    }
+
+    /// Decompresses containers with the given decompressor instead of the default codec.
+    ///
+    /// Only containers without XTEA encryption go through the decompressor, such as
+    /// definitions and reference tables. All clones made afterwards share it.
+    pub fn with_decompressor<D: Decompressor + 'static>(mut self, decompressor: D) -> Self {
+        self.decompressor = Some(Arc::new(decompressor));
+        self
+    }
+
+    /// Decodes definitions fetched through [`FetchDefinition`](definition::osrs::FetchDefinition)
+    /// with the given policy for unknown opcodes.
+    ///
+    /// Defaults to [`UnknownOpcodePolicy::Error`], warnings of the other policies are dropped.
+    pub fn with_unknown_opcode_policy(mut self, policy: UnknownOpcodePolicy) -> Self {
+        self.decode_options.on_unknown_opcode = policy;
+        self
+    }
+
+    /// Decodes definitions fetched through [`FetchDefinition`](definition::osrs::FetchDefinition)
+    /// as they are stored by caches of the given revision.
+    ///
+    /// Caches don't store their revision. Without one npcs and objects are decoded with the
+    /// layout of current caches, see [`LoaderOptions::revision`].
+    pub fn with_revision(mut self, revision: u16) -> Self {
+        self.decode_options.revision = Some(revision);
+        self
+    }
+
     /// Generate a checksum based on the current cache.
     ///
     /// The `Checksum` acts as a validator for individual cache files. Any
@@ -180,6 +374,51 @@ impl Cache {
         Checksum::new(self)
     }
 
+    /// Generate a checksum containing only the given indices, in the given order.
+    ///
+    /// Useful for clients that expect a subset of the indices or a specific order.
+    pub fn checksum_for(&self, indices: &[u8]) -> crate::Result<Checksum> {
+        Checksum::with_indices(self, indices)
+    }
+
+    /// Lists the archives to download to bring this cache up to date with `remote`.
+    ///
+    /// The checksum table only has a crc and version per index, so the plan holds the
+    /// reference table `(255, index_id)` of every index whose crc or version differs
+    /// from `remote`. Comparing the downloaded reference table with the local one tells
+    /// which archives of that index changed.
+    ///
+    /// The entries of `remote` are in index id order, like the checksum table sent by an
+    /// update server. Indices this cache can't read are always in the plan, indices that
+    /// only exist locally are left out.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the checksum of this cache can't be generated, see
+    /// [`checksum`](Cache::checksum).
+    pub fn update_plan(&self, remote: &Checksum) -> crate::Result<Vec<(u8, u32)>> {
+        let checksum = self.checksum()?;
+        let local: HashMap<u8, &Entry> = checksum
+            .index_ids
+            .iter()
+            .copied()
+            .zip(checksum.iter())
+            .collect();
+
+        let plan = remote
+            .iter()
+            .enumerate()
+            .filter(|&(index_id, remote)| {
+                local.get(&(index_id as u8)).is_none_or(|local| {
+                    local.crc() != remote.crc() || local.version() != remote.version()
+                })
+            })
+            .map(|(index_id, _)| (REFERENCE_TABLE_ID, index_id as u32))
+            .collect();
+
+        Ok(plan)
+    }
+
     /// Generate a checksum based on the current cache with RSA encryption.
     ///
     /// `RsaChecksum` wraps a regular `Checksum` with the added benefit of
@@ -199,9 +438,23 @@ impl Cache {
     /// exist the `IndexNotFound` or `ArchiveNotFound` errors are returned,
     /// respectively.
     ///
-    /// Any other errors such as sector validation failures or failed parsers
-    /// should be considered a bug.
+    /// The header of every sector is checked against the archive id, the expected chunk
+    /// and the index id while the archive is reassembled. The first corrupted sector
+    /// fails the read with a `SectorArchiveMismatch`, `SectorChunkMismatch` or
+    /// `SectorIndexMismatch`. Any other errors such as failed parsers should be
+    /// considered a bug.
+    ///
+    /// Archive ids are `u32`, [`ArchiveId`](index::ArchiveId) converts them to and
+    /// from the `u16` ids of definitions without truncating.
+    ///
+    /// Sector headers store the chunk number as a `u16` which wraps after 65535, the
+    /// chunk of archives spanning more than 65536 sectors (about 32 MiB) is validated
+    /// modulo 65536.
     pub fn read(&self, index_id: u8, archive_id: u32) -> crate::Result<Buffer<Encoded>> {
+        if let Some(overlay) = self.overlay_for(index_id, archive_id) {
+            return overlay.read(index_id, archive_id);
+        }
+
         let index = self
             .indices
             .get(&index_id)
@@ -215,13 +468,359 @@ impl Cache {
                 arc: archive_id,
             }))?;
 
-        let buffer = self.data.read(archive)?;
+        let mut buffer = Vec::with_capacity(archive.length);
+        self.write_archive(archive, &mut buffer)?;
 
         assert_eq!(buffer.len(), archive.length);
 
+        Ok(Buffer::from(buffer))
+    }
+
+    /// Writes the data blocks of every sector of an archive, following the same chain
+    /// as [`sectors`](Cache::sectors).
+    fn write_archive<W: Write>(&self, archive: &ArchiveRef, writer: &mut W) -> crate::Result<()> {
+        for sector in sector::Sectors::new(&self.data, *archive) {
+            writer.write_all(sector?.data_block)?;
+        }
+
+        Ok(())
+    }
+
+    /// Walks the sectors of an archive without copying them out of the data file.
+    ///
+    /// Every sector header is validated like in [`read`](Cache::read). The iterator
+    /// stops after the first sector that fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IndexNotFound` or `ArchiveNotFound` if the archive doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rscache::Cache;
+    /// # fn main() -> Result<(), rscache::Error> {
+    /// # let cache = Cache::new("./data/osrs_cache")?;
+    /// let mut len = 0;
+    /// for sector in cache.sectors(2, 10)? {
+    ///     len += sector?.data_block.len();
+    /// }
+    ///
+    /// assert_eq!(len, cache.read(2, 10)?.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sectors(&self, index_id: u8, archive_id: u32) -> crate::Result<sector::Sectors<'_>> {
+        if let Some(overlay) = self.overlay_for(index_id, archive_id) {
+            return overlay.sectors(index_id, archive_id);
+        }
+
+        let index = self
+            .indices
+            .get(&index_id)
+            .ok_or(RuneFsError::Read(ReadError::IndexNotFound(index_id)))?;
+
+        let archive = index
+            .archive_refs
+            .get(&archive_id)
+            .ok_or(RuneFsError::Read(ReadError::ArchiveNotFound {
+                idx: index_id,
+                arc: archive_id,
+            }))?;
+
+        Ok(sector::Sectors::new(&self.data, *archive))
+    }
+
+    /// Returns a handle for repeated reads within one index.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IndexNotFound` if the cache doesn't contain the index.
+    pub fn index(&self, index_id: u8) -> crate::Result<index::Index<'_>> {
+        let index = self
+            .indices
+            .get(&index_id)
+            .ok_or(RuneFsError::Read(ReadError::IndexNotFound(index_id)))?;
+
+        Ok(index::Index::new(self, index_id, index))
+    }
+
+    /// Maps the name hash of every named archive in an index to its archive id.
+    ///
+    /// Archives without a name have a hash of 0 and are left out, indices without
+    /// names give an empty map. Hashes are made with [`djd2::hash`](util::djd2::hash).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReadError::IndexNotFound` if the index doesn't exist.
+    pub fn name_index(&self, index_id: u8) -> crate::Result<HashMap<i32, u32>> {
+        let index = self
+            .indices
+            .get(&index_id)
+            .ok_or(RuneFsError::Read(ReadError::IndexNotFound(index_id)))?;
+
+        Ok(index
+            .metadata
+            .iter()
+            .filter(|archive| archive.name_hash != 0)
+            .map(|archive| (archive.name_hash, archive.id))
+            .collect())
+    }
+
+    /// Resolves the names of a wordlist to archive ids, names that aren't in the index
+    /// are left out.
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`name_index`](Cache::name_index).
+    pub fn resolve_names<I, T>(&self, index_id: u8, names: I) -> crate::Result<HashMap<String, u32>>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let name_index = self.name_index(index_id)?;
+
+        Ok(names
+            .into_iter()
+            .filter_map(|name| {
+                let archive_id = *name_index.get(&util::djd2::hash(&name))?;
+                Some((name.as_ref().to_owned(), archive_id))
+            })
+            .collect())
+    }
+
+    /// Checks if the reference table of the given index lists the archive.
+    ///
+    /// When an overlay is used the archive exists if either cache lists it.
+    pub fn archive_exists(&self, index_id: u8, archive_id: u32) -> bool {
+        let exists = self.indices.get(&index_id).is_some_and(|index| {
+            index
+                .metadata
+                .iter()
+                .any(|archive| archive.id == archive_id)
+        });
+
+        exists
+            || self
+                .overlay
+                .as_ref()
+                .is_some_and(|overlay| overlay.archive_exists(index_id, archive_id))
+    }
+
+    /// The number of archives listed in the reference table of the given index.
+    ///
+    /// Only the base cache is counted when an overlay is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IndexNotFound` if the cache doesn't contain the index.
+    pub fn archive_count(&self, index_id: u8) -> crate::Result<usize> {
+        let index = self
+            .indices
+            .get(&index_id)
+            .ok_or(RuneFsError::Read(ReadError::IndexNotFound(index_id)))?;
+
+        Ok(index.metadata.iter().len())
+    }
+
+    /// The number of archives listed in the reference tables of all indices.
+    ///
+    /// The reference table index itself isn't counted, see
+    /// [`archive_count`](Cache::archive_count).
+    pub fn total_archives(&self) -> usize {
+        (&*self.indices)
+            .into_iter()
+            .filter(|(&index_id, _)| index_id != REFERENCE_TABLE_ID)
+            .map(|(_, index)| index.metadata.iter().len())
+            .sum()
+    }
+
+    /// Retrieves the decompressed length of an archive without decompressing it.
+    ///
+    /// Only the first sector of the archive is read, and only its container header is
+    /// parsed: the compression type followed by the compressed length and, when the
+    /// container is compressed, the decompressed length.
+    ///
+    /// Note: for containers encrypted with XTEA the decompressed length is also
+    /// encrypted and will not be accurate.
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`read`](Cache::read) for more details.
+    pub fn archive_decompressed_len(&self, index_id: u8, archive_id: u32) -> crate::Result<usize> {
+        // The header is at most 9 bytes, a first sector always holds it whole.
+        let buffer = match self.sectors(index_id, archive_id)?.next() {
+            Some(sector) => sector?.data_block,
+            None => &[],
+        };
+
+        let (buffer, compression) = be_u8(buffer)?;
+        let (buffer, compressed_len) = be_u32(buffer)?;
+
+        let len = if compression == 0 {
+            compressed_len
+        } else {
+            be_u32(buffer)?.1
+        };
+
+        Ok(len as usize)
+    }
+
+    /// Sums the container sizes of every archive listed in the reference tables.
+    ///
+    /// Only the container header in the first sector of every archive is parsed, see
+    /// [`archive_decompressed_len`](Cache::archive_decompressed_len). The reference tables
+    /// themselves aren't counted. The XTEA encrypted location archives of the map index
+    /// are only counted in `encrypted_count`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a listed archive can't be read.
+    pub fn stats(&self) -> crate::Result<CacheStats> {
+        let mut stats = CacheStats::default();
+        let encrypted = util::location_name_hashes();
+
+        for (&index_id, index) in &*self.indices {
+            if index_id == REFERENCE_TABLE_ID {
+                continue;
+            }
+            stats.index_count += 1;
+
+            for archive in &index.metadata {
+                let Some(archive_ref) = index.archive_refs.get(&archive.id) else {
+                    continue;
+                };
+                if archive_ref.length == 0 {
+                    continue;
+                }
+
+                stats.archive_count += 1;
+                if index_id == util::MAPS_INDEX && encrypted.contains(&archive.name_hash) {
+                    stats.encrypted_count += 1;
+                    continue;
+                }
+                stats.total_compressed += archive_ref.length as u64;
+                stats.total_decompressed +=
+                    self.archive_decompressed_len(index_id, archive.id)? as u64;
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Retrieves and decodes data corresponding to the given index and archive.
+    ///
+    /// When the archive cache is enabled the decoded archive is served from memory if
+    /// present, otherwise it is read, decoded and stored in the archive cache.
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`read`](Cache::read) for more details. Decoding
+    /// failures are returned as well.
+    pub fn read_decoded(&self, index_id: u8, archive_id: u32) -> crate::Result<Buffer<Decoded>> {
+        let Some(archive_cache) = &self.archive_cache else {
+            return self.decode(self.read(index_id, archive_id)?);
+        };
+
+        let key = (index_id, archive_id);
+        let cached = archive_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key);
+        if let Some(buffer) = cached {
+            return Ok(Buffer::from(buffer));
+        }
+
+        let buffer = self.decode(self.read(index_id, archive_id)?)?;
+        archive_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key, buffer.to_vec());
+
         Ok(buffer)
     }
 
+    /// Retrieves and decodes an archive and hands the decoded data to `f`.
+    ///
+    /// Saves the boilerplate of reading, decoding and parsing an archive in separate steps,
+    /// the decoded buffer is borrowed by `f` instead of being copied out.
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`read_decoded`](Cache::read_decoded), errors returned
+    /// by `f` are passed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rscache::Cache;
+    /// # fn main() -> Result<(), rscache::Error> {
+    /// # let cache = Cache::new("./data/osrs_cache")?;
+    /// let len = cache.read_decoded_with(2, 10, |buffer| Ok(buffer.len()))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_decoded_with<T, F>(&self, index_id: u8, archive_id: u32, f: F) -> crate::Result<T>
+    where
+        F: FnOnce(&[u8]) -> crate::Result<T>,
+    {
+        let buffer = self.read_decoded(index_id, archive_id)?;
+
+        f(buffer.as_slice())
+    }
+
+    /// Reads and decodes every given `(index_id, archive_id)` pair into the archive cache.
+    ///
+    /// Warms the archive cache at startup so later reads have predictable latency. Archives
+    /// that are already cached are skipped. Does nothing if the archive cache isn't enabled.
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`read_decoded`](Cache::read_decoded) for more details.
+    pub fn prefetch(&self, coords: &[(u8, u32)]) -> crate::Result<()> {
+        let Some(archive_cache) = &self.archive_cache else {
+            return Ok(());
+        };
+
+        for &(index_id, archive_id) in coords {
+            let cached = archive_cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .contains((index_id, archive_id));
+            if !cached {
+                self.read_decoded(index_id, archive_id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Hit and miss counters of the archive cache, `None` if it isn't enabled.
+    pub fn archive_cache_stats(&self) -> Option<ArchiveCacheStats> {
+        self.archive_cache.as_ref().map(|archive_cache| {
+            archive_cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .stats()
+        })
+    }
+
+    /// Decodes a container, through the custom decompressor if one was given.
+    pub(crate) fn decode(&self, buffer: Buffer<Encoded>) -> crate::Result<Buffer<Decoded>> {
+        let Some(decompressor) = &self.decompressor else {
+            return Ok(buffer.decode()?);
+        };
+
+        let container = Container::parse(buffer.as_slice())?;
+        let data = decompressor.decompress(
+            container.compression,
+            container.payload,
+            container.decompressed_len,
+        )?;
+
+        Ok(Buffer::from(data))
+    }
+
     pub(crate) fn read_archive(&self, archive: &ArchiveRef) -> crate::Result<Buffer<Encoded>> {
         self.read(archive.index_id, archive.id)
     }
@@ -238,6 +837,10 @@ impl Cache {
         archive_id: u32,
         writer: &mut W,
     ) -> crate::Result<()> {
+        if let Some(overlay) = self.overlay_for(index_id, archive_id) {
+            return overlay.read_into_writer(index_id, archive_id, writer);
+        }
+
         let index = self
             .indices
             .get(&index_id)
@@ -250,7 +853,114 @@ impl Cache {
                 idx: index_id,
                 arc: archive_id,
             }))?;
-        Ok(self.data.read_into_writer(archive, writer)?)
+
+        self.write_archive(archive, writer)
+    }
+
+    /// Reads the raw container of an archive without decoding it.
+    ///
+    /// The container holds the compression, the lengths, the payload and the optional
+    /// version trailer, which is the input for [`codec::decode`](crate::codec::decode).
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`read`](Cache::read) for more details.
+    pub fn read_archive_raw(&self, index_id: u8, archive_id: u32) -> crate::Result<Vec<u8>> {
+        let mut container = Vec::new();
+        self.read_into_writer(index_id, archive_id, &mut container)?;
+
+        Ok(container)
+    }
+
+    /// Reads the raw container of an archive straight from its location in the data file.
+    ///
+    /// Unlike [`read_archive_raw`](Cache::read_archive_raw) the index isn't consulted,
+    /// the sector, length and id of `archive` are used as is. Archives with an id above
+    /// `u16::MAX` automatically use the expanded sector headers. Overlays are not
+    /// taken into account.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the sectors of `archive` are invalid, e.g. when `archive` doesn't
+    /// belong to this cache.
+    pub fn read_archive_struct(&self, archive: &ArchiveRef) -> crate::Result<Vec<u8>> {
+        let mut container = Vec::with_capacity(archive.length);
+        self.write_archive(archive, &mut container)?;
+
+        Ok(container)
+    }
+
+    /// Frames an archive the way the OSRS update server sends it to the client.
+    ///
+    /// The response starts with the index id, the archive id as u16, the compression
+    /// and the compressed length followed by the container data without its version.
+    /// After every 512 bytes a `0xFF` separator byte is inserted. Requesting archive
+    /// 255 of index 255 responds with the encoded [`Checksum`].
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`read`](Cache::read) for more details. Archive ids
+    /// that don't fit in a `u16` return `ReadError::ArchiveIdOutOfRange`.
+    pub fn read_update_response(&self, index_id: u8, archive_id: u32) -> crate::Result<Vec<u8>> {
+        self.update_response(index_id, archive_id, &mut Vec::new())
+    }
+
+    /// Frames a batch of archives with [`read_update_response`](Cache::read_update_response).
+    ///
+    /// The archives are read into the same scratch buffer, so only the responses
+    /// themselves are allocated.
+    ///
+    /// # Errors
+    ///
+    /// Fails on the first archive that can't be read.
+    pub fn read_update_responses(&self, requests: &[(u8, u32)]) -> crate::Result<Vec<Vec<u8>>> {
+        let mut scratch = Vec::new();
+
+        requests
+            .iter()
+            .map(|&(index_id, archive_id)| self.update_response(index_id, archive_id, &mut scratch))
+            .collect()
+    }
+
+    fn update_response(
+        &self,
+        index_id: u8,
+        archive_id: u32,
+        scratch: &mut Vec<u8>,
+    ) -> crate::Result<Vec<u8>> {
+        const BLOCK_LEN: usize = 512;
+
+        let framed_id = u16::try_from(ArchiveId(archive_id))?;
+        scratch.clear();
+        if index_id == 255 && archive_id == 255 {
+            scratch.extend(self.checksum()?.encode()?.finalize());
+        } else {
+            self.read_into_writer(index_id, archive_id, scratch)?;
+            // Only the reference tables are sent with their version.
+            if index_id != 255 {
+                scratch.truncate(scratch.len().saturating_sub(2));
+            }
+        }
+
+        let (data, compression) = be_u8(scratch.as_slice())?;
+        let (data, length) = be_u32(data)?;
+
+        let mut framed = Vec::with_capacity(data.len() + 8);
+        framed.push(index_id);
+        framed.extend(framed_id.to_be_bytes());
+        framed.push(compression);
+        framed.extend(length.to_be_bytes());
+        framed.extend(data);
+
+        let first_len = framed.len().min(BLOCK_LEN);
+        let mut response = Vec::with_capacity(framed.len() + framed.len() / (BLOCK_LEN - 1));
+        response.extend(&framed[..first_len]);
+        for block in framed[first_len..].chunks(BLOCK_LEN - 1) {
+            response.push(0xFF);
+            response.extend(block);
+        }
+
+        Ok(response)
     }
 
     /// Retrieves the huffman table.
@@ -260,12 +970,26 @@ impl Cache {
     pub fn huffman_table(&self) -> crate::Result<Buffer<Decoded>> {
         let index_id = 10;
 
+        // The archive can be read from an overlay, its length is checked by `read` against
+        // the reference it was actually read from.
         let archive = self.archive_by_name(index_id, "huffman")?;
         let buffer = self.read_archive(archive)?;
 
-        assert_eq!(buffer.len(), archive.length);
+        self.decode(buffer)
+    }
 
-        Ok(buffer.decode()?)
+    /// The overlay to read an archive from, if it contains the archive.
+    ///
+    /// Empty entries in the overlay's index don't shadow the base cache, a sparse overlay
+    /// only has to contain the archives it replaces.
+    fn overlay_for(&self, index_id: u8, archive_id: u32) -> Option<&Cache> {
+        self.overlay.as_deref().filter(|overlay| {
+            overlay
+                .indices
+                .get(&index_id)
+                .and_then(|index| index.archive_refs.get(&archive_id))
+                .is_some_and(|archive| archive.length != 0)
+        })
     }
 
     pub(crate) fn archive_by_name<T: AsRef<str>>(
@@ -301,9 +1025,137 @@ impl Cache {
     }
 }
 
+/// Collects the options of a [`Cache`] before opening it.
+///
+/// Every option defaults to what [`Cache::new`] uses, so `Cache::builder().open(path)`
+/// is the same as `Cache::new(path)`.
+#[derive(Clone, Debug, Default)]
+pub struct CacheBuilder {
+    overlay: Option<PathBuf>,
+    cache_capacity: Option<usize>,
+    decompressor: Option<Arc<dyn Decompressor>>,
+    mmap: Option<bool>,
+    decode_options: LoaderOptions,
+}
+
+impl CacheBuilder {
+    /// Memory-maps the main data file when `true` (the default), reads it into memory
+    /// when `false`. The overlay, if any, is opened the same way.
+    ///
+    /// A memory-mapped data file must not be truncated while the cache is open, reading
+    /// past its new end raises `SIGBUS` on Unix and kills the process. Use `false` when
+    /// another process may rewrite the data file, such as the cache of a running client.
+    pub fn mmap(mut self, mmap: bool) -> Self {
+        self.mmap = Some(mmap);
+        self
+    }
+
+    /// Decodes fetched definitions with the given policy, see
+    /// [`Cache::with_unknown_opcode_policy`].
+    pub fn unknown_opcode_policy(mut self, policy: UnknownOpcodePolicy) -> Self {
+        self.decode_options.on_unknown_opcode = policy;
+        self
+    }
+
+    /// Decodes fetched definitions for the given cache revision, see [`Cache::with_revision`].
+    pub fn revision(mut self, revision: u16) -> Self {
+        self.decode_options.revision = Some(revision);
+        self
+    }
+
+    /// Layers the cache in `path` on top, see [`Cache::with_overlay`].
+    pub fn overlay<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.overlay = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Keeps up to `capacity` decoded archives in memory, see [`Cache::with_archive_cache`].
+    pub fn cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = Some(capacity);
+        self
+    }
+
+    /// Decompresses containers with the given decompressor, see [`Cache::with_decompressor`].
+    pub fn decompressor<D: Decompressor + 'static>(mut self, decompressor: D) -> Self {
+        self.decompressor = Some(Arc::new(decompressor));
+        self
+    }
+
+    /// Opens the cache in `path` with the collected options.
+    ///
+    /// # Errors
+    ///
+    /// See the error section on [`Cache::new`], the overlay has to be valid as well.
+    pub fn open<P: AsRef<Path>>(self, path: P) -> crate::Result<Cache> {
+        let mmap = self.mmap.unwrap_or(true);
+        let mut cache = Cache::open(path.as_ref(), mmap)?;
+        if let Some(overlay) = &self.overlay {
+            cache.overlay = Some(Arc::new(Cache::open(overlay, mmap)?));
+        }
+        cache.decode_options = self.decode_options;
+        if let Some(capacity) = self.cache_capacity {
+            cache = cache.with_archive_cache(capacity);
+        }
+        cache.decompressor = self.decompressor;
+
+        Ok(cache)
+    }
+}
+
 #[cfg(test)]
 fn is_normal<T: Send + Sync + Sized + Unpin>() {}
 #[test]
 fn normal_types() {
+    use definition::osrs::{
+        AreaDefinition, EnumDefinition, FrameDefinition, HitsplatDefinition, IdentikitDefinition,
+        ItemDefinition, MapDefinition, ModelDefinition, NpcDefinition, ObjectDefinition,
+        ParamDefinition, SequenceDefinition, SkeletonDefinition, SpotAnimDefinition,
+        SpriteDefinition,
+    };
+    use loader::osrs::{
+        AreaLoader, EnumLoader, FrameLoader, HitsplatLoader, IdentikitLoader, ItemLoader,
+        LocationLoader, MapLoader, ModelLoader, NpcLoader, ObjectLoader, OverlayLoader,
+        ParamLoader, ScriptLoader, SequenceLoader, SpotAnimLoader, SpriteLoader, TextureLoader,
+        VarbitLoader, WorldMapLoader,
+    };
+
     is_normal::<Cache>();
+    is_normal::<Checksum>();
+
+    is_normal::<ItemDefinition>();
+    is_normal::<NpcDefinition>();
+    is_normal::<ObjectDefinition>();
+    is_normal::<MapDefinition>();
+    is_normal::<SpriteDefinition>();
+    is_normal::<ModelDefinition>();
+    is_normal::<IdentikitDefinition>();
+    is_normal::<HitsplatDefinition>();
+    is_normal::<AreaDefinition>();
+    is_normal::<EnumDefinition>();
+    is_normal::<SequenceDefinition>();
+    is_normal::<SpotAnimDefinition>();
+    is_normal::<FrameDefinition>();
+    is_normal::<SkeletonDefinition>();
+    is_normal::<ParamDefinition>();
+
+    is_normal::<IdentikitLoader>();
+    is_normal::<HitsplatLoader>();
+    is_normal::<AreaLoader>();
+    is_normal::<EnumLoader>();
+    is_normal::<SequenceLoader>();
+    is_normal::<SpotAnimLoader>();
+    is_normal::<ItemLoader>();
+    is_normal::<NpcLoader>();
+    is_normal::<ObjectLoader>();
+    is_normal::<OverlayLoader>();
+    is_normal::<ParamLoader>();
+    is_normal::<TextureLoader>();
+    is_normal::<SpriteLoader>();
+    is_normal::<VarbitLoader>();
+    is_normal::<WorldMapLoader>();
+    is_normal::<MapLoader<'_>>();
+    is_normal::<LocationLoader<'_>>();
+    is_normal::<ModelLoader<'_>>();
+    is_normal::<ScriptLoader<'_>>();
+    is_normal::<FrameLoader<'_>>();
 }
@@ -6,7 +6,7 @@
 //! example to help you make your own loader if you desperately need it.
 //!
 //! ```
-//! use std::{collections::HashMap, io::{ self, BufReader, }};
+//! use std::{collections::HashMap, io::{ self, Cursor, }};
 //! use rscache::{
 //!     Cache, extension::ReadExt,
 //!     definition::osrs::{ Definition, FetchDefinition },
@@ -56,14 +56,14 @@
 //!
 //! impl Definition for CustomDefinition {
 //!     fn new(id: u16, buffer: &[u8]) -> Result<Self, rscache::Error> {
-//!         let mut reader = BufReader::new(buffer);
+//!         let mut reader = Cursor::new(buffer);
 //!         let def = decode_buffer(id, &mut reader)?;
 //!
 //!         Ok(def)
 //!     }
 //! }
 //! 
-//! fn decode_buffer(id: u16, reader: &mut BufReader<&[u8]>) -> io::Result<CustomDefinition> {
+//! fn decode_buffer(id: u16, reader: &mut Cursor<&[u8]>) -> io::Result<CustomDefinition> {
 //!     // Parse the buffer into a definition.
 //!     let mut def = CustomDefinition {
 //!         id,
@@ -80,9 +80,8 @@
 //!             2 => { def.name = reader.read_string()?; break; },
 //!             // Skipping the rest of the buffer for the sake of the example,
 //!             // every opcode should be parsed into values of the definition.
-//!             _ => { if reader.buffer().len() == 0 { break; } }
-//!             // Should normally be:
-//!             // _ => unreachable!()
+//!             _ => { if reader.position() as usize == reader.get_ref().len() { break; } }
+//!             // Unknown opcodes should normally return `ReadError::UnknownOpcode`.
 //!         }
 //!     }
 //!
@@ -90,9 +89,79 @@
 //! }
 //! ```
 
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::DecodeWarning;
+
 /// OSRS loaders.
 pub mod osrs;
 /// RS3 loaders.
 #[cfg(feature = "rs3")]
 #[cfg_attr(docsrs, doc(cfg(feature = "rs3")))]
 pub mod rs3;
+
+/// Ids of the definitions that differ between two loaders, sorted in ascending order.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct DefinitionChanges {
+    /// Definitions that only exist in the newer loader.
+    pub added: Vec<u16>,
+    /// Definitions that only exist in the older loader.
+    pub removed: Vec<u16>,
+    /// Definitions that exist in both loaders but aren't equal.
+    pub changed: Vec<u16>,
+}
+
+/// What happened while a loader decoded its definitions.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct LoaderReport {
+    /// Definitions that were decoded.
+    pub loaded: usize,
+    /// Empty definition buffers that were skipped.
+    pub skipped: usize,
+    /// Ids and errors of the definitions that failed to decode, these are left out.
+    pub errors: Vec<(u16, String)>,
+    /// Ids and warnings of the definitions that were decoded with problems.
+    pub warnings: Vec<(u16, DecodeWarning)>,
+    /// How long reading and decoding took.
+    pub elapsed: Duration,
+}
+
+/// How a decoder handles an opcode it doesn't know.
+///
+/// The payload size of an unknown opcode isn't known, so the decoder can't find the
+/// next opcode on its own.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum UnknownOpcodePolicy {
+    /// Panic, the decoder is out of date with the cache.
+    Panic,
+    /// Fail the definition with `ReadError::UnknownOpcode`.
+    #[default]
+    Error,
+    /// Skip the given number of payload bytes and keep decoding.
+    Skip(usize),
+    /// Keep what was decoded up to the opcode and report a
+    /// [`DecodeWarning::UnknownOpcode`].
+    Warn,
+}
+
+/// Options for loaders that decode their definitions one by one.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct LoaderOptions {
+    pub on_unknown_opcode: UnknownOpcodePolicy,
+    /// The revision of the cache, caches don't store it themselves.
+    ///
+    /// Npc and object opcodes whose payload changed between revisions are decoded with the
+    /// layout of this revision. `None` decodes them with the layout of current caches.
+    pub revision: Option<u16>,
+}
+
+impl DefinitionChanges {
+    /// Whether both loaders contain exactly the same definitions.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
@@ -1,6 +1,9 @@
-use std::collections::{
-    hash_map::{self, Entry},
-    HashMap,
+use std::{
+    collections::{
+        hash_map::{self, Entry},
+        HashMap, HashSet,
+    },
+    time::Instant,
 };
 
 #[cfg(feature = "serde")]
@@ -8,11 +11,91 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     definition::osrs::{
-        Definition, FetchDefinition, ItemDefinition, LocationDefinition, MapDefinition,
-        NpcDefinition, ObjectDefinition,
+        archive_files, merge_chunks, AreaDefinition, Definition, EnumDefinition, FetchDefinition,
+        FrameDefinition, HitsplatDefinition, IdentikitDefinition, ItemDefinition, ItemOpcodes,
+        LocationDefinition, MapDefinition, ModelDefinition, NpcDefinition, ObjectDefinition,
+        OverlayDefinition, ParamDefinition, SequenceDefinition, SkeletonDefinition,
+        SpotAnimDefinition, SpriteDefinition, TextureDefinition, VarbitDefinition,
+        WorldMapDefinition, BODY_PART_COUNT,
     },
-    Cache,
+    index::ArchiveId,
+    loader::{DefinitionChanges, LoaderOptions, LoaderReport, UnknownOpcodePolicy},
+    util, Cache,
 };
+use runefs::ArchiveFileGroup;
+
+/// Loads all map area definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct AreaLoader(HashMap<u16, AreaDefinition>);
+
+impl_osrs_loader!(AreaLoader, AreaDefinition, index_id: 2, archive_id: 35);
+
+/// Loads all enum definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct EnumLoader(HashMap<u16, EnumDefinition>);
+
+impl_osrs_loader!(EnumLoader, EnumDefinition, index_id: 2, archive_id: 8);
+
+impl EnumLoader {
+    /// The name of a music track as shown in the music player.
+    ///
+    /// Track ids are the keys of the music track names enum `enum_id`, they are not the
+    /// archive ids of the music index. The id of that enum depends on the revision, it is
+    /// [`MUSIC_TRACK_NAMES_ENUM`](crate::definition::osrs::MUSIC_TRACK_NAMES_ENUM) in
+    /// the caches this crate is tested against.
+    ///
+    /// Returns `None` if the enum doesn't exist, has no string values or doesn't have the
+    /// track.
+    pub fn track_name(&self, enum_id: u16, id: i32) -> Option<&str> {
+        self.load(enum_id)?
+            .string_values
+            .get(&id)
+            .map(String::as_str)
+    }
+}
+
+/// Loads all hitsplat definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct HitsplatLoader(HashMap<u16, HitsplatDefinition>);
+
+impl_osrs_loader!(HitsplatLoader, HitsplatDefinition, index_id: 2, archive_id: 32);
+
+/// Loads all identikit definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct IdentikitLoader(HashMap<u16, IdentikitDefinition>);
+
+impl_osrs_loader!(IdentikitLoader, IdentikitDefinition, index_id: 2, archive_id: 3);
+
+impl IdentikitLoader {
+    /// The default kit id of every body part for a new character, indexed by body part.
+    ///
+    /// Just like the client this is the selectable kit with the lowest id for each body
+    /// part, body parts without a selectable kit (e.g. the female jaw) are `None`.
+    pub fn defaults(&self, female: bool) -> [Option<u16>; BODY_PART_COUNT] {
+        let offset = if female { BODY_PART_COUNT } else { 0 };
+        let mut defaults = [None; BODY_PART_COUNT];
+
+        for kit in self.0.values().filter(|kit| kit.selectable()) {
+            let Some(part) = kit
+                .body_part
+                .and_then(|part| (part as usize).checked_sub(offset))
+            else {
+                continue;
+            };
+            if let Some(default) = defaults.get_mut(part) {
+                if default.is_none_or(|id| kit.id < id) {
+                    *default = Some(kit.id);
+                }
+            }
+        }
+
+        defaults
+    }
+}
 
 /// Loads all item definitions from the current cache.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -21,6 +104,209 @@ pub struct ItemLoader(HashMap<u16, ItemDefinition>);
 
 impl_osrs_loader!(ItemLoader, ItemDefinition, index_id: 2, archive_id: 10);
 
+impl ItemLoader {
+    /// Same as `new` but keeps going when an item fails to decode and reports what happened.
+    ///
+    /// Items with unknown opcodes are rejected with `UnknownOpcodePolicy::Error`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the item archive can't be read, failing items are listed in the report.
+    pub fn new_with_report(cache: &Cache) -> crate::Result<(Self, LoaderReport)> {
+        let options = LoaderOptions {
+            on_unknown_opcode: UnknownOpcodePolicy::Error,
+            ..cache.decode_options
+        };
+
+        Self::new_with_options(cache, options)
+    }
+
+    /// Same as [`new_with_report`](ItemLoader::new_with_report) but decodes every item
+    /// with the given options.
+    ///
+    /// Items that are rejected by `UnknownOpcodePolicy::Error` are listed as errors and
+    /// items decoded under `UnknownOpcodePolicy::Warn` are listed as warnings.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the item archive can't be read, failing items are listed in the report.
+    pub fn new_with_options(
+        cache: &Cache,
+        options: LoaderOptions,
+    ) -> crate::Result<(Self, LoaderReport)> {
+        let start = Instant::now();
+        let mut report = LoaderReport::default();
+        let mut items = HashMap::new();
+
+        for file in archive_files(cache, 2, 10)? {
+            if file.data.is_empty() {
+                report.skipped += 1;
+                continue;
+            }
+
+            let id = u16::try_from(ArchiveId(file.id))?;
+            match ItemDefinition::new_with_options(id, &file.data, options) {
+                Ok((item, warnings)) => {
+                    items.insert(id, item);
+                    report.loaded += 1;
+                    report
+                        .warnings
+                        .extend(warnings.into_iter().map(|warning| (id, warning)));
+                }
+                Err(error) => report.errors.push((id, error.to_string())),
+            }
+        }
+        report.elapsed = start.elapsed();
+
+        Ok((Self(items), report))
+    }
+
+    /// Follows the `bought_link` of a bought item to the canonical tradable item.
+    ///
+    /// Items that aren't bought variants resolve to themselves.
+    pub fn ge_base_item(&self, id: u16) -> Option<&ItemDefinition> {
+        let item = self.load(id)?;
+
+        match (item.bought_template, item.bought_link) {
+            (Some(_), Some(link)) => self.load(link),
+            _ => Some(item),
+        }
+    }
+
+    /// Follows the links of a noted or placeholder item to the real item it stands for.
+    ///
+    /// Noted items resolve through `noted_id` and placeholders through `placeholder_id`,
+    /// real items resolve to themselves. Returns `None` if the linked item doesn't exist.
+    pub fn canonical(&self, id: u16) -> Option<&ItemDefinition> {
+        let item = self.load(id)?;
+        let base = match (item.noted_template, item.placeholder_template_id) {
+            (Some(_), _) => item.noted_id,
+            (_, Some(_)) => item.placeholder_id,
+            _ => None,
+        };
+
+        match base {
+            Some(base) => self.load(base),
+            None => Some(item),
+        }
+    }
+
+    /// Loads an item with the fields of a noted item filled in from its unnoted item.
+    ///
+    /// Noted items only reference the unnoted item through `noted_id` and the note
+    /// template through `noted_template`. Like the client the name, cost, membership
+    /// and tradability are taken from the unnoted item and the inventory model from
+    /// the note template. Items that aren't noted are returned as is.
+    pub fn resolve_noted_fields(&self, id: u16) -> Option<ItemDefinition> {
+        let mut item = self.load(id)?.clone();
+        let (Some(template), Some(unnoted)) = (item.noted_template, item.noted_id) else {
+            return Some(item);
+        };
+        let template = self.load(template)?;
+        let unnoted = self.load(unnoted)?;
+
+        item.inventory_model_data = template.inventory_model_data.clone();
+        item.name = unnoted.name.clone();
+        item.cost = unnoted.cost;
+        item.members_only = unnoted.members_only;
+        item.tradable = unnoted.tradable;
+        item.stackable = true;
+
+        Some(item)
+    }
+
+    /// Iterates over the items that can be used in free-to-play worlds.
+    ///
+    /// Noted and bought items take their membership from the item they link to, like
+    /// in [`resolve_noted_fields`](ItemLoader::resolve_noted_fields).
+    pub fn free_to_play(&self) -> impl Iterator<Item = &ItemDefinition> {
+        self.0.values().filter(|item| !self.is_members(item))
+    }
+
+    /// Iterates over the items that are only usable on members worlds.
+    ///
+    /// See [`free_to_play`](ItemLoader::free_to_play) for how linked items are handled.
+    pub fn members(&self) -> impl Iterator<Item = &ItemDefinition> {
+        self.0.values().filter(|item| self.is_members(item))
+    }
+
+    fn is_members(&self, item: &ItemDefinition) -> bool {
+        let linked = match (item.noted_template, item.bought_template) {
+            (Some(_), _) => item.noted_id,
+            (_, Some(_)) => item.bought_link,
+            _ => None,
+        };
+
+        match linked.and_then(|id| self.load(id)) {
+            Some(linked) => linked.members_only,
+            None => item.members_only,
+        }
+    }
+
+    /// Counts how often every opcode appears in the item definitions of the cache.
+    ///
+    /// The raw buffers are scanned without decoding them, which reveals opcodes the
+    /// decoder doesn't handle yet. Scanning a definition stops at its first unknown
+    /// opcode, which is still counted.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the item archive can't be read or a payload is cut short.
+    pub fn opcode_histogram(cache: &Cache) -> crate::Result<HashMap<u8, usize>> {
+        let items: HashMap<u16, ItemOpcodes> = ItemOpcodes::fetch_from_archive(cache, 2, 10)?;
+        let mut histogram = HashMap::new();
+
+        for opcode in items.values().flat_map(|opcodes| &opcodes.0) {
+            *histogram.entry(*opcode).or_insert(0) += 1;
+        }
+
+        Ok(histogram)
+    }
+
+    /// Compares this loader against an older one.
+    pub fn diff(&self, older: &Self) -> DefinitionChanges {
+        let mut changes = DefinitionChanges::default();
+
+        for (id, item) in &self.0 {
+            match older.0.get(id) {
+                None => changes.added.push(*id),
+                Some(old) if old != item => changes.changed.push(*id),
+                Some(_) => {}
+            }
+        }
+        changes.removed = older
+            .0
+            .keys()
+            .filter(|id| !self.0.contains_key(id))
+            .copied()
+            .collect();
+
+        changes.added.sort_unstable();
+        changes.removed.sort_unstable();
+        changes.changed.sort_unstable();
+
+        changes
+    }
+
+    /// Compares this loader against a snapshot made with `save_bincode`.
+    ///
+    /// Handy for changelogs, only the current cache has to be available.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the snapshot can't be opened or isn't a valid snapshot.
+    #[cfg(feature = "bincode")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bincode")))]
+    pub fn changes_since<P: AsRef<std::path::Path>>(
+        &self,
+        baseline: P,
+    ) -> crate::Result<DefinitionChanges> {
+        let baseline = Self::load_bincode(baseline)?;
+
+        Ok(self.diff(&baseline))
+    }
+}
+
 /// Loads all npc definitions from the current cache.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Eq, PartialEq, Debug, Default)]
@@ -35,6 +321,97 @@ pub struct ObjectLoader(HashMap<u16, ObjectDefinition>);
 
 impl_osrs_loader!(ObjectLoader, ObjectDefinition, index_id: 2, archive_id: 6);
 
+impl ObjectLoader {
+    /// Finds all objects that have `action` in one of their action slots, e.g. "Mine".
+    ///
+    /// Actions are compared case-insensitively, the result is sorted by id.
+    pub fn with_action(&self, action: &str) -> Vec<&ObjectDefinition> {
+        let mut objects: Vec<_> = self
+            .0
+            .values()
+            .filter(|obj| {
+                obj.actions
+                    .iter()
+                    .any(|slot| slot.eq_ignore_ascii_case(action))
+            })
+            .collect();
+        objects.sort_unstable_by_key(|obj| obj.id);
+
+        objects
+    }
+}
+
+/// Loads all overlay definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct OverlayLoader(HashMap<u16, OverlayDefinition>);
+
+impl_osrs_loader!(OverlayLoader, OverlayDefinition, index_id: 2, archive_id: 4);
+
+/// Loads all param definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct ParamLoader(HashMap<u16, ParamDefinition>);
+
+impl_osrs_loader!(ParamLoader, ParamDefinition, index_id: 2, archive_id: 11);
+
+/// Loads all animation definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct SequenceLoader(HashMap<u16, SequenceDefinition>);
+
+impl_osrs_loader!(SequenceLoader, SequenceDefinition, index_id: 2, archive_id: 12);
+
+/// Loads all spot animation definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct SpotAnimLoader(HashMap<u16, SpotAnimDefinition>);
+
+impl_osrs_loader!(SpotAnimLoader, SpotAnimDefinition, index_id: 2, archive_id: 13);
+
+/// Loads all texture definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct TextureLoader(HashMap<u16, TextureDefinition>);
+
+impl_osrs_loader!(TextureLoader, TextureDefinition, index_id: 9, archive_id: 0);
+
+/// Loads all sprite definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct SpriteLoader(HashMap<u16, SpriteDefinition>);
+
+impl_osrs_loader!(SpriteLoader, SpriteDefinition, index_id: 8);
+
+/// Loads all varbit definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct VarbitLoader(HashMap<u16, VarbitDefinition>);
+
+impl_osrs_loader!(VarbitLoader, VarbitDefinition, index_id: 2, archive_id: 14);
+
+/// Loads all world map definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct WorldMapLoader(HashMap<u16, WorldMapDefinition>);
+
+impl WorldMapLoader {
+    pub fn new(cache: &Cache) -> crate::Result<Self> {
+        let index_id = 19;
+        let archive = cache.archive_by_name(index_id, "details")?;
+        let map = WorldMapDefinition::fetch_from_archive(cache, index_id, archive.id)?;
+
+        Ok(Self(map))
+    }
+
+    pub fn load(&self, id: u16) -> Option<&WorldMapDefinition> {
+        self.0.get(&id)
+    }
+}
+
+impl_bincode_for_loader!(WorldMapLoader);
+impl_iter_for_loader!(WorldMapLoader, u16, WorldMapDefinition);
+
 /// Loads maps definitions lazily from the current cache.
 #[derive(Debug)]
 pub struct MapLoader<'cache> {
@@ -60,8 +437,10 @@ impl<'cache> MapLoader<'cache> {
             let x = id >> 8;
             let y = id & 0xFF;
 
-            let map_archive = self.cache.archive_by_name(5, format!("m{}_{}", x, y))?;
-            let buffer = self.cache.read_archive(map_archive)?.decode()?;
+            let map_archive = self
+                .cache
+                .archive_by_name(util::MAPS_INDEX, format!("m{}_{}", x, y))?;
+            let buffer = self.cache.decode(self.cache.read_archive(map_archive)?)?;
 
             entry.insert(MapDefinition::new(id, &buffer)?);
         }
@@ -70,6 +449,150 @@ impl<'cache> MapLoader<'cache> {
     }
 }
 
+/// Loads model definitions lazily from the current cache.
+#[derive(Debug)]
+pub struct ModelLoader<'cache> {
+    cache: &'cache Cache,
+    models: HashMap<u16, ModelDefinition>,
+}
+
+impl<'cache> ModelLoader<'cache> {
+    /// Make a new `ModelLoader`.
+    ///
+    /// This takes a `Cache` by references with a `'cache` lifetime.
+    /// All the model definitions are loaded lazily where the `&'cache Cache` is used
+    /// to cache them internally on load.
+    pub fn new(cache: &'cache Cache) -> Self {
+        Self {
+            cache,
+            models: HashMap::new(),
+        }
+    }
+
+    pub fn load(&mut self, id: u16) -> crate::Result<&ModelDefinition> {
+        if let Entry::Vacant(entry) = self.models.entry(id) {
+            let buffer = self.cache.decode(self.cache.read(7, id as u32)?)?;
+
+            entry.insert(ModelDefinition::new(id, &buffer)?);
+        }
+
+        Ok(&self.models[&id])
+    }
+}
+
+/// Loads the compiled client scripts lazily from the current cache.
+///
+/// Scripts are returned as the raw bytecode buffers, they are not decompiled.
+#[derive(Debug)]
+pub struct ScriptLoader<'cache> {
+    cache: &'cache Cache,
+    scripts: HashMap<u16, Vec<u8>>,
+}
+
+impl<'cache> ScriptLoader<'cache> {
+    /// Make a new `ScriptLoader`.
+    ///
+    /// This takes a `Cache` by references with a `'cache` lifetime.
+    /// All the scripts are loaded lazily where the `&'cache Cache` is used
+    /// to cache them internally on load.
+    pub fn new(cache: &'cache Cache) -> Self {
+        Self {
+            cache,
+            scripts: HashMap::new(),
+        }
+    }
+
+    pub fn load(&mut self, id: u16) -> crate::Result<&[u8]> {
+        if let Entry::Vacant(entry) = self.scripts.entry(id) {
+            let buffer = self.cache.decode(self.cache.read(12, id as u32)?)?;
+
+            entry.insert(buffer.finalize());
+        }
+
+        Ok(&self.scripts[&id])
+    }
+}
+
+/// Loads animation frames and their skeletons lazily from the current cache.
+#[derive(Debug)]
+pub struct FrameLoader<'cache> {
+    cache: &'cache Cache,
+    frames: HashMap<u32, FrameDefinition>,
+    skeletons: HashMap<u16, SkeletonDefinition>,
+    loaded_archives: HashSet<u16>,
+}
+
+impl<'cache> FrameLoader<'cache> {
+    /// Make a new `FrameLoader`.
+    ///
+    /// This takes a `Cache` by references with a `'cache` lifetime.
+    /// All the frames are loaded lazily where the `&'cache Cache` is used
+    /// to cache them internally on load.
+    pub fn new(cache: &'cache Cache) -> Self {
+        Self {
+            cache,
+            frames: HashMap::new(),
+            skeletons: HashMap::new(),
+            loaded_archives: HashSet::new(),
+        }
+    }
+
+    /// Loads a frame by the frame id used in sequences.
+    ///
+    /// The whole frame archive is decoded on the first load of one of its frames.
+    /// Returns `None` if the archive doesn't contain the frame.
+    pub fn load(&mut self, frame_id: u32) -> crate::Result<Option<&FrameDefinition>> {
+        let archive_id = (frame_id >> 16) as u16;
+        if self.loaded_archives.insert(archive_id) {
+            if let Err(error) = self.load_archive(archive_id) {
+                self.loaded_archives.remove(&archive_id);
+                return Err(error);
+            }
+        }
+
+        Ok(self.frames.get(&frame_id))
+    }
+
+    /// Loads a skeleton, frames reference theirs by `skeleton_id`.
+    pub fn skeleton(&mut self, id: u16) -> crate::Result<&SkeletonDefinition> {
+        if let Entry::Vacant(entry) = self.skeletons.entry(id) {
+            let buffer = self.cache.decode(self.cache.read(1, id as u32)?)?;
+
+            entry.insert(SkeletonDefinition::new(id, &buffer)?);
+        }
+
+        Ok(&self.skeletons[&id])
+    }
+
+    fn load_archive(&mut self, archive_id: u16) -> crate::Result<()> {
+        let valid_ids = self
+            .cache
+            .indices
+            .get(&0)
+            .and_then(|index| {
+                index
+                    .metadata
+                    .iter()
+                    .find(|archive| archive.id == archive_id as u32)
+            })
+            .map(|archive| archive.valid_ids.clone())
+            .unwrap_or_default();
+        let buffer = self.cache.decode(self.cache.read(0, archive_id as u32)?)?;
+        let files = merge_chunks(ArchiveFileGroup::from_buffer(&buffer, valid_ids.len()));
+
+        for (file, &file_id) in files.iter().zip(&valid_ids) {
+            let skeleton_id = FrameDefinition::skeleton_id(&file.data)?;
+            let skeleton = self.skeleton(skeleton_id)?;
+            let frame_id = u32::from(archive_id) << 16 | file_id;
+            let frame = FrameDefinition::new(frame_id, &file.data, skeleton)?;
+
+            self.frames.insert(frame_id, frame);
+        }
+
+        Ok(())
+    }
+}
+
 /// Loads location definitions lazily from the current cache.
 #[derive(Debug)]
 pub struct LocationLoader<'cache> {
@@ -99,7 +622,9 @@ impl<'cache> LocationLoader<'cache> {
             let x = id >> 8;
             let y = id & 0xFF;
 
-            let loc_archive = self.cache.archive_by_name(5, format!("l{}_{}", x, y))?;
+            let loc_archive = self
+                .cache
+                .archive_by_name(util::MAPS_INDEX, format!("l{}_{}", x, y))?;
             let buffer = self
                 .cache
                 .read_archive(loc_archive)?
@@ -112,4 +637,3 @@ impl<'cache> LocationLoader<'cache> {
         Ok(&self.locations[&id])
     }
 }
-    
\ No newline at end of file
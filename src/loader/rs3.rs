@@ -3,8 +3,10 @@ use std::collections::{hash_map, HashMap};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use runefs::{IndexMetadata, REFERENCE_TABLE_ID};
+
 use crate::{
-    definition::rs3::{FetchDefinition, ItemDefinition},
+    definition::rs3::{Definition, FetchDefinition, ItemDefinition, SpriteDefinition},
     Cache,
 };
 
@@ -14,3 +16,41 @@ use crate::{
 pub struct ItemLoader(HashMap<u32, ItemDefinition>);
 
 impl_rs3_loader!(ItemLoader, ItemDefinition, index_id: 19);
+
+/// Loads all sprite definitions from the current cache.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct SpriteLoader(HashMap<u32, SpriteDefinition>);
+
+impl SpriteLoader {
+    /// Every archive of the sprite index holds exactly one sprite.
+    pub fn new(cache: &Cache) -> crate::Result<Self> {
+        let index_id = 8;
+        let buffer = cache.decode(cache.read(REFERENCE_TABLE_ID, index_id as u32)?)?;
+        let archives = IndexMetadata::from_buffer(buffer)?;
+
+        let mut map = HashMap::new();
+        for archive in &archives {
+            let buffer = cache.read(index_id, archive.id)?;
+            if buffer.is_empty() {
+                continue;
+            }
+
+            let buffer = cache.decode(buffer)?;
+            if buffer.is_empty() {
+                continue;
+            }
+
+            map.insert(archive.id, SpriteDefinition::new(archive.id, &buffer)?);
+        }
+
+        Ok(Self(map))
+    }
+
+    pub fn load(&self, id: u32) -> Option<&SpriteDefinition> {
+        self.0.get(&id)
+    }
+}
+
+impl_bincode_for_loader!(SpriteLoader);
+impl_iter_for_loader!(SpriteLoader, u32, SpriteDefinition);
@@ -0,0 +1,87 @@
+//! Zero-copy access to the sectors of the data file.
+
+use runefs::{
+    error::{Error as RuneFsError, ParseError},
+    ArchiveRef, DataBlocks, Sector, SectorHeaderSize, SECTOR_SIZE,
+};
+
+/// Iterator over the sectors of one archive, obtained through [`Cache::sectors`].
+///
+/// Every [`Sector`] borrows its data block straight from the memory map of the data
+/// file, walking an archive this way doesn't allocate. Concatenating the data blocks
+/// gives the same buffer as [`Cache::read`].
+///
+/// [`Cache::sectors`]: crate::Cache::sectors
+/// [`Cache::read`]: crate::Cache::read
+pub struct Sectors<'a> {
+    data: &'a [u8],
+    archive: ArchiveRef,
+    header_size: SectorHeaderSize,
+    blocks: DataBlocks,
+    chunk: usize,
+    current: usize,
+}
+
+impl<'a> Sectors<'a> {
+    pub(crate) fn new(data: &'a [u8], archive: ArchiveRef) -> Self {
+        Self {
+            data,
+            archive,
+            header_size: SectorHeaderSize::from(&archive),
+            blocks: archive.data_blocks(),
+            chunk: 0,
+            current: archive.sector,
+        }
+    }
+
+    fn sector(&self, len: usize) -> crate::Result<Sector<'a>> {
+        let offset = self.current * SECTOR_SIZE;
+        let block = self
+            .data
+            .get(offset..offset + len)
+            .ok_or(RuneFsError::Parse(ParseError::Sector(self.current)))?;
+
+        let sector = Sector::new(block, &self.header_size)?;
+        // The chunk is stored as a `u16` in the header and wraps for very large archives.
+        sector
+            .header
+            .validate(
+                self.archive.id,
+                self.chunk % 0x1_0000,
+                self.archive.index_id,
+            )
+            .map_err(RuneFsError::Read)?;
+
+        Ok(sector)
+    }
+}
+
+impl<'a> Iterator for Sectors<'a> {
+    type Item = crate::Result<Sector<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.blocks.next()?;
+        let sector = self.sector(len);
+
+        match &sector {
+            Ok(sector) => {
+                self.current = sector.header.next;
+                self.chunk += 1;
+            }
+            // Stop after the first error, the next sector can't be found.
+            Err(_) => while self.blocks.next().is_some() {},
+        }
+
+        Some(sector)
+    }
+}
+
+impl std::fmt::Debug for Sectors<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sectors")
+            .field("archive", &self.archive)
+            .field("chunk", &self.chunk)
+            .field("current", &self.current)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,19 +1,37 @@
 //! Helpful utility functions, macros and structs.
 
+mod archive_cache;
 #[allow(unused_assignments)]
 mod huffman;
 #[allow(clippy::many_single_char_names, clippy::too_many_lines)]
 mod isaac_rand;
 
+pub(crate) use archive_cache::ArchiveCache;
+pub use archive_cache::ArchiveCacheStats;
 pub use huffman::Huffman;
 pub use isaac_rand::IsaacRand;
 
 use std::{
-    collections::HashMap,
-    io::{self, BufReader},
+    collections::{HashMap, HashSet},
+    io::{self, Cursor, Read},
+    sync::OnceLock,
 };
 
-use crate::extension::ReadExt;
+use crate::{definition::osrs::VarbitDefinition, error::ReadError, extension::ReadExt};
+
+/// Totals over all archives of a cache, see [`Cache::stats`](crate::Cache::stats).
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct CacheStats {
+    pub index_count: usize,
+    pub archive_count: usize,
+    /// XTEA encrypted location archives, these are left out of the totals because their
+    /// decompressed length can't be read without the keys.
+    pub encrypted_count: usize,
+    /// The size of all containers as stored on disk.
+    pub total_compressed: u64,
+    /// The size of all archives once decompressed, according to their container headers.
+    pub total_decompressed: u64,
+}
 
 macro_rules! impl_osrs_loader {
     ($ldr:ident, $def:ty, index_id: $idx_id:expr $(, archive_id: $arc_id:expr)?) => {
@@ -36,6 +54,7 @@ macro_rules! impl_osrs_loader {
             }
         }
 
+        impl_bincode_for_loader!($ldr);
         impl_iter_for_loader!($ldr, u16, $def);
     };
 }
@@ -55,10 +74,45 @@ macro_rules! impl_rs3_loader {
             }
         }
 
+        impl_bincode_for_loader!($ldr);
         impl_iter_for_loader!($ldr, u32, $def);
     };
 }
 
+macro_rules! impl_bincode_for_loader {
+    ($ldr:ident) => {
+        #[cfg(feature = "bincode")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "bincode")))]
+        impl $ldr {
+            /// Writes a snapshot of all the decoded definitions to `path`.
+            ///
+            /// The snapshot can be restored with `load_bincode` without touching the cache.
+            ///
+            /// # Errors
+            ///
+            /// Fails if the file can't be created or serialization fails.
+            pub fn save_bincode<P: AsRef<std::path::Path>>(&self, path: P) -> crate::Result<()> {
+                let writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+                bincode::serialize_into(writer, &self.0)?;
+
+                Ok(())
+            }
+
+            /// Restores a loader from a snapshot made with `save_bincode`.
+            ///
+            /// # Errors
+            ///
+            /// Fails if the file can't be opened or doesn't contain a valid snapshot.
+            pub fn load_bincode<P: AsRef<std::path::Path>>(path: P) -> crate::Result<Self> {
+                let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+                let map = bincode::deserialize_from(reader)?;
+
+                Ok(Self(map))
+            }
+        }
+    };
+}
+
 macro_rules! impl_iter_for_loader {
     ($ldr:ident, $id:ty, $def:ty) => {
         impl $ldr {
@@ -71,6 +125,27 @@ macro_rules! impl_iter_for_loader {
             pub fn iter_mut(&mut self) -> hash_map::IterMut<'_, $id, $def> {
                 self.0.iter_mut()
             }
+
+            /// The number of loaded definitions.
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.0.is_empty()
+            }
+        }
+
+        impl std::ops::Index<$id> for $ldr {
+            type Output = $def;
+
+            /// Same as `load` but panics if the definition doesn't exist.
+            #[inline]
+            fn index(&self, id: $id) -> &Self::Output {
+                &self.0[&id]
+            }
         }
 
         impl IntoIterator for $ldr {
@@ -121,35 +196,59 @@ pub mod djd2 {
     /// ```
     pub fn hash<T: AsRef<str>>(string: T) -> i32 {
         let string = string.as_ref();
-        let mut hash = 0;
+        let mut hash: i32 = 0;
 
         for index in 0..string.len() {
             hash =
-                string.chars().nth(index).unwrap_or_else(|| {
+                (string.chars().nth(index).unwrap_or_else(|| {
                     panic!("index {} not valid in str len {}", index, string.len())
-                }) as i32
-                    + ((hash << 5) - hash);
+                }) as i32)
+                    .wrapping_add((hash << 5).wrapping_sub(hash));
         }
         hash
     }
 }
 
+/// The index holding the map and location archives of every region.
+pub(crate) const MAPS_INDEX: u8 = 5;
+
+/// The name hashes of the location archive of every region, named `l{x}_{y}`.
+///
+/// Location archives are the XTEA encrypted archives of the [`MAPS_INDEX`].
+pub(crate) fn location_name_hashes() -> &'static HashSet<i32> {
+    static HASHES: OnceLock<HashSet<i32>> = OnceLock::new();
+
+    HASHES.get_or_init(|| {
+        (0..=u8::MAX)
+            .flat_map(|x| (0..=u8::MAX).map(move |y| djd2::hash(format!("l{}_{}", x, y))))
+            .collect()
+    })
+}
+
 /// Useful for decoding parameters when reading from definition buffers.
 ///
 /// # Errors
 ///
-/// Can return `std::io::Error` if reading from the `BufReader<&[u8]>` fails.
-pub fn read_parameters(reader: &mut BufReader<&[u8]>) -> io::Result<HashMap<u32, String>> {
+/// Returns `ReadError::MalformedParams` wrapped in a `std::io::Error` if the parameter
+/// block is cut short or contains an unknown value type, which converts into
+/// `Error::Read` when it reaches the crate's error type.
+pub fn read_parameters<R: Read>(reader: &mut R) -> io::Result<HashMap<u32, String>> {
     let len = reader.read_u8()?;
     let mut map = HashMap::new();
 
-    for _ in 0..len {
-        let is_string = reader.read_u8()? == 1;
-        let key = reader.read_u24()?;
+    for entry in 0..len as usize {
+        let malformed = |_| ReadError::MalformedParams { entry };
+
+        let is_string = match reader.read_u8().map_err(malformed)? {
+            0 => false,
+            1 => true,
+            _ => return Err(ReadError::MalformedParams { entry }.into()),
+        };
+        let key = reader.read_u24().map_err(malformed)?;
         let value = if is_string {
-            reader.read_string()?
+            reader.read_string().map_err(malformed)?
         } else {
-            reader.read_i32()?.to_string()
+            reader.read_i32().map_err(malformed)?.to_string()
         };
 
         map.insert(key, value);
@@ -157,3 +256,149 @@ pub fn read_parameters(reader: &mut BufReader<&[u8]>) -> io::Result<HashMap<u32,
 
     Ok(map)
 }
+
+/// Reads a big smart where `u16::MAX` means the id is not set.
+pub(crate) fn read_optional_smart(reader: &mut Cursor<&[u8]>) -> io::Result<Option<u32>> {
+    let id = reader.read_smart()?;
+
+    Ok(if id == u16::MAX as u32 {
+        None
+    } else {
+        Some(id)
+    })
+}
+
+/// The amount of bytes that haven't been read yet.
+pub(crate) fn remaining(reader: &Cursor<&[u8]>) -> usize {
+    reader
+        .get_ref()
+        .len()
+        .saturating_sub(reader.position() as usize)
+}
+
+/// Collects the non-empty strings of an option or action array, in slot order.
+pub(crate) fn present(slots: &[String]) -> Vec<&str> {
+    slots
+        .iter()
+        .filter(|slot| !slot.is_empty())
+        .map(String::as_str)
+        .collect()
+}
+
+/// Consumes the payload of an opcode that is known but not decoded.
+///
+/// `skipped` maps opcodes to the amount of bytes they carry. Returns `true` if the
+/// opcode was found in `skipped` and its payload was read.
+///
+/// # Errors
+///
+/// Can return `std::io::Error` if the payload is shorter than expected.
+pub(crate) fn skip_opcode(
+    reader: &mut Cursor<&[u8]>,
+    skipped: &[(u8, usize)],
+    opcode: u8,
+) -> io::Result<bool> {
+    let Some(&(_, len)) = skipped.iter().find(|(skipped, _)| *skipped == opcode) else {
+        return Ok(false);
+    };
+
+    reader.skip(len)?;
+
+    Ok(true)
+}
+
+/// Resolves the value a morphing npc or object should use.
+///
+/// When a varbit is given only its bit range is extracted from `varp_value`,
+/// otherwise the whole varp value is used.
+///
+/// # Examples
+///
+/// ```
+/// use rscache::{definition::osrs::VarbitDefinition, util};
+///
+/// let varbit = VarbitDefinition {
+///     least_significant_bit: 4,
+///     most_significant_bit: 7,
+///     ..VarbitDefinition::default()
+/// };
+///
+/// assert_eq!(util::resolve_config(Some(&varbit), 0b1010_0011), 0b1010);
+/// assert_eq!(util::resolve_config(None, 0b1010_0011), 0b1010_0011);
+/// ```
+pub fn resolve_config(varbit: Option<&VarbitDefinition>, varp_value: i32) -> i32 {
+    let Some(varbit) = varbit else {
+        return varp_value;
+    };
+
+    let lsb = u32::from(varbit.least_significant_bit.min(31));
+    let msb = u32::from(varbit.most_significant_bit.clamp(varbit.least_significant_bit, 31));
+    let mask = u32::MAX >> (31 - (msb - lsb));
+
+    ((varp_value as u32 >> lsb) & mask) as i32
+}
+
+/// Formats a buffer as offset, hex and ascii columns, the way `hexdump -C` does.
+///
+/// Every line holds 16 bytes, bytes outside the printable ascii range show up as `.`
+/// in the ascii column. Useful for inspecting decoded archives.
+///
+/// # Examples
+///
+/// ```
+/// use rscache::util;
+///
+/// let dump = util::hexdump(b"Hello, world!\n");
+/// assert_eq!(
+///     dump,
+///     "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a        |Hello, world!.|\n"
+/// );
+/// ```
+pub fn hexdump(buffer: &[u8]) -> String {
+    use std::fmt::Write;
+
+    let mut dump = String::new();
+    for (line, chunk) in buffer.chunks(16).enumerate() {
+        let _ = write!(dump, "{:08x}  ", line * 16);
+        for index in 0..16 {
+            if index == 8 {
+                dump.push(' ');
+            }
+            match chunk.get(index) {
+                Some(byte) => {
+                    let _ = write!(dump, "{byte:02x} ");
+                }
+                None => dump.push_str("   "),
+            }
+        }
+
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        let _ = writeln!(dump, " |{ascii}|");
+    }
+
+    dump
+}
+
+/// Asserts that a definition decoder consumed the entire buffer.
+///
+/// # Errors
+///
+/// Returns `ReadError::TrailingBytes` when there are unread bytes left.
+pub(crate) fn ensure_consumed(reader: &Cursor<&[u8]>) -> Result<(), ReadError> {
+    let remaining = remaining(reader);
+
+    if remaining == 0 {
+        Ok(())
+    } else {
+        Err(ReadError::TrailingBytes { remaining })
+    }
+}
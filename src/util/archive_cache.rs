@@ -0,0 +1,79 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Hit and miss counters of the opt-in archive cache.
+///
+/// Every miss results in the archive being read and decoded from the cache files.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ArchiveCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Least recently used cache of decoded archives, keyed by index and archive id.
+#[derive(Debug)]
+pub(crate) struct ArchiveCache {
+    capacity: usize,
+    archives: HashMap<(u8, u32), Vec<u8>>,
+    order: VecDeque<(u8, u32)>,
+    stats: ArchiveCacheStats,
+}
+
+impl ArchiveCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            archives: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            stats: ArchiveCacheStats::default(),
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: (u8, u32)) -> Option<Vec<u8>> {
+        match self.archives.get(&key) {
+            Some(buffer) => {
+                self.stats.hits += 1;
+                let buffer = buffer.clone();
+                self.touch(key);
+
+                Some(buffer)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub(crate) fn contains(&self, key: (u8, u32)) -> bool {
+        self.archives.contains_key(&key)
+    }
+
+    pub(crate) fn insert(&mut self, key: (u8, u32), buffer: Vec<u8>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.archives.insert(key, buffer).is_some() {
+            self.touch(key);
+            return;
+        }
+
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.archives.remove(&evicted);
+            }
+        }
+    }
+
+    pub(crate) const fn stats(&self) -> ArchiveCacheStats {
+        self.stats
+    }
+
+    fn touch(&mut self, key: (u8, u32)) {
+        if let Some(pos) = self.order.iter().position(|k| *k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::Cache;
+
 /// Decompresses chat messages.
 /// 
 /// # Examples
@@ -88,7 +90,7 @@ impl Huffman {
 
                 i_11 = 0;
                 while i_11 < b_6 as i32 {
-                    i_12_1 = ((i32::min_value()) as u32 >> i_11) as i32;
+                    i_12_1 = ((i32::MIN) as u32 >> i_11) as i32;
                     if i_8 & i_12_1 != 0 {
                         if keys[i_10 as usize] == 0 {
                             keys[i_10 as usize] = i_4;
@@ -120,6 +122,27 @@ impl Huffman {
         Self { keys }
     }
 
+    /// Initializes the Huffman struct with the huffman table of the given cache.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the huffman table can't be read from the cache.
+    pub fn from_cache(cache: &Cache) -> crate::Result<Self> {
+        Ok(Self::new(&cache.huffman_table()?))
+    }
+
+    /// Decompresses the given buffer into a chat message.
+    ///
+    /// Unlike [`decompress`](Huffman::decompress) an empty message is returned when
+    /// the decompressed length is 0. Invalid UTF-8 is replaced with `U+FFFD`.
+    pub fn decompress_str(&self, compressed: &[u8], decompressed_len: usize) -> String {
+        if decompressed_len == 0 {
+            return String::new();
+        }
+
+        String::from_utf8_lossy(&self.decompress(compressed, decompressed_len)).into_owned()
+    }
+
     /// Decompresses the given buffer.
     ///
     /// The buffer is normally an encoded chat message which will be decoded into
@@ -16,6 +16,27 @@ mod osrs {
         assert!(Cache::new("./wrong/path").is_err());
     }
 
+    #[cfg(feature = "archive")]
+    #[test]
+    fn from_archive() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::{fs::File, path::Path};
+
+        let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("osrs_cache.tar.gz");
+        let encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::fast());
+        let mut tarball = tar::Builder::new(encoder);
+        tarball
+            .append_dir_all("osrs_cache", "./data/osrs_cache")
+            .unwrap();
+        tarball.into_inner().unwrap().finish().unwrap();
+
+        let cache = Cache::from_archive(&path).unwrap();
+        let buffer = cache.read(2, 10).unwrap();
+
+        let hash = test_util::hash(&buffer);
+        assert_eq!(&hash, "c6ee1518e9a39a42ecaf946c6c84a942cb3102f4");
+    }
+
     #[test]
     fn huffman_table() {
         let cache = test_util::osrs_cache();
@@ -25,6 +46,17 @@ mod osrs {
         assert_eq!(&hash, "664e89cf25a0af7da138dd0f3904ca79cd1fe767");
         assert_eq!(buffer.len(), 256);
     }
+
+    #[test]
+    fn huffman_decompress() {
+        let cache = test_util::osrs_cache();
+        let huffman = rscache::util::Huffman::from_cache(&cache).unwrap();
+
+        let message = huffman.decompress_str(&[174, 128, 35, 32, 208, 96], 8);
+
+        assert_eq!(message, "rs-cache");
+        assert_eq!(huffman.decompress_str(&[], 0), "");
+    }
 }
 
 #[cfg(all(test, feature = "rs3"))]
@@ -34,6 +34,36 @@ fn validate() {
     assert!(checksum.validate(&crcs).is_ok());
 }
 
+#[test]
+fn subset() {
+    let cache = test_util::osrs_cache();
+    let checksum = cache.checksum_for(&[2, 0, 19]).unwrap();
+
+    assert_eq!(checksum.index_count(), 3);
+    assert_eq!(checksum.iter().count(), 3);
+
+    let crcs = [16840364, 1593884597, 2751169400];
+    assert!(checksum.validate(&crcs).is_ok());
+}
+
+#[test]
+fn entries() {
+    use rscache::checksum::Entry;
+
+    let checksum: Checksum = [(1, 10), (2, 20), (3, 30)]
+        .into_iter()
+        .map(|(crc, version)| Entry::new(crc, version))
+        .collect();
+
+    assert_eq!(checksum.len(), 3);
+    assert!(!checksum.is_empty());
+    assert_eq!(checksum.entries()[1].crc(), 2);
+    assert!(Checksum::default().is_empty());
+
+    let versions: Vec<u32> = checksum.into_iter().map(|entry| entry.version()).collect();
+    assert_eq!(versions, vec![10, 20, 30]);
+}
+
 #[test]
 fn invalid_crc() {
     use rscache::error::ValidateError;
@@ -57,6 +87,101 @@ fn invalid_crc() {
     );
 }
 
+#[test]
+fn invalid_crc_detailed() {
+    let cache = test_util::osrs_cache();
+    let checksum = Checksum::new(&cache).unwrap();
+
+    let crcs = [
+        1593884597, 1029608590, 16840364, 4209098954, 3716821437, 165713182, 686540367, 4262755489,
+        2208636505, 3047082366, 586413816, 2890424900, 3411535427, 3178880569, 153718440,
+        3849392898, 3628627685, 2813112885, 1461700456, 2751169400, 2927815226,
+    ];
+
+    assert_eq!(
+        checksum.validate_crcs_detailed(&crcs),
+        Err(vec![(3, 4209099954, 4209098954)])
+    );
+}
+
+#[test]
+fn validate_full() {
+    use rscache::checksum::{Entry, EntryMismatch};
+
+    let cache = test_util::osrs_cache();
+    let checksum = Checksum::new(&cache).unwrap();
+    let mut entries: Vec<Entry> = checksum.iter().cloned().collect();
+
+    assert_eq!(checksum.validate_full(&entries), Ok(()));
+
+    let entry = &entries[2];
+    entries[2] = Entry::new(entry.crc(), entry.version() + 1);
+    let entry = &entries[5];
+    entries[5] = Entry::new(entry.crc() + 1, entry.version());
+    entries.pop();
+
+    assert_eq!(
+        checksum.validate_full(&entries),
+        Err(vec![
+            (2, EntryMismatch::Version),
+            (5, EntryMismatch::Crc),
+            (20, EntryMismatch::Both)
+        ])
+    );
+}
+
+#[test]
+fn update_plan() {
+    use rscache::checksum::Entry;
+
+    let cache = test_util::osrs_cache();
+    let checksum = Checksum::new(&cache).unwrap();
+
+    assert_eq!(cache.update_plan(&checksum).unwrap(), vec![]);
+
+    let remote: Checksum = checksum
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| match index {
+            7 => Entry::new(entry.crc() ^ 1, entry.version() + 1),
+            _ => entry.clone(),
+        })
+        .collect();
+
+    assert_eq!(cache.update_plan(&remote).unwrap(), vec![(255, 7)]);
+
+    let remote: Checksum = checksum.iter().take(3).cloned().collect();
+
+    assert_eq!(cache.update_plan(&remote).unwrap(), vec![]);
+}
+
+#[test]
+fn update_plan_unreadable_index() {
+    use rscache::Cache;
+    use std::{
+        fs::{self, OpenOptions},
+        io::{Seek, SeekFrom, Write},
+    };
+
+    let remote = Checksum::new(&test_util::osrs_cache()).unwrap();
+    let path = test_util::osrs_cache_copy("update_plan_unreadable_index");
+
+    // Drop index 3 and point its reference table at sector 1, which belongs to another
+    // archive. The cache still opens but its checksum has no entry for index 3.
+    fs::remove_file(path.join("main_file_cache.idx3")).unwrap();
+    let mut idx = OpenOptions::new()
+        .write(true)
+        .open(path.join("main_file_cache.idx255"))
+        .unwrap();
+    idx.seek(SeekFrom::Start(3 * 6 + 3)).unwrap();
+    idx.write_all(&[0, 0, 1]).unwrap();
+
+    let cache = Cache::new(&path).unwrap();
+    assert_eq!(cache.checksum().unwrap().len(), remote.len() - 1);
+
+    assert_eq!(cache.update_plan(&remote).unwrap(), vec![(255, 3)]);
+}
+
 #[test]
 fn invalid_len() {
     use rscache::error::ValidateError;
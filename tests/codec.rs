@@ -0,0 +1,66 @@
+use rscache::codec::{self, Compression};
+
+const DATA: &[u8] = b"rs-cache container round trip";
+
+#[test]
+fn round_trip_without_revision() {
+    for compression in [Compression::None, Compression::Bzip2, Compression::Gzip] {
+        let container = codec::encode(compression, DATA, None).unwrap();
+        let (data, revision) = codec::decode(&container).unwrap();
+
+        assert_eq!(data, DATA);
+        assert_eq!(revision, None);
+    }
+}
+
+#[test]
+fn round_trip_with_revision() {
+    for compression in [Compression::None, Compression::Bzip2, Compression::Gzip] {
+        let container = codec::encode(compression, DATA, Some(211)).unwrap();
+        let (data, revision) = codec::decode(&container).unwrap();
+
+        assert_eq!(data, DATA);
+        assert_eq!(revision, Some(211));
+    }
+}
+
+#[test]
+fn revision_trailer() {
+    let container = codec::encode(Compression::None, DATA, Some(0x1234)).unwrap();
+
+    // compression, length, data, revision
+    assert_eq!(container.len(), 1 + 4 + DATA.len() + 2);
+    assert_eq!(&container[container.len() - 2..], &[0x12, 0x34]);
+}
+
+#[test]
+fn compression_levels() {
+    let data = DATA.repeat(64);
+
+    for compression in [Compression::Bzip2, Compression::Gzip] {
+        let fast = codec::encode_with_level(compression, &data, Some(211), 1).unwrap();
+        let best = codec::encode_with_level(compression, &data, Some(211), 9).unwrap();
+
+        assert_eq!(codec::decode(&fast).unwrap(), (data.clone(), Some(211)));
+        assert_eq!(codec::decode(&best).unwrap(), (data.clone(), Some(211)));
+    }
+}
+
+#[test]
+fn default_compression_level() {
+    for (compression, level) in [(Compression::Bzip2, 1), (Compression::Gzip, 9)] {
+        let container = codec::encode_with_level(compression, DATA, None, level).unwrap();
+
+        assert_eq!(container, codec::encode(compression, DATA, None).unwrap());
+    }
+}
+
+#[test]
+fn invalid_compression_level() {
+    let large = vec![0; 100_001];
+
+    assert!(codec::encode_with_level(Compression::Gzip, DATA, None, 10).is_err());
+    assert!(codec::encode_with_level(Compression::Bzip2, DATA, None, 0).is_err());
+    assert!(codec::encode_with_level(Compression::Bzip2, &large, None, 9).is_err());
+    assert!(codec::encode_with_level(Compression::Bzip2, &large, None, 1).is_ok());
+}
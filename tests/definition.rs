@@ -0,0 +1,915 @@
+mod osrs {
+    /// Every opcode definition stops at an unknown opcode and reports where it was.
+    #[test]
+    fn unknown_opcode() {
+        use rscache::{
+            definition::osrs::{
+                AreaDefinition, Definition, EnumDefinition, HitsplatDefinition,
+                IdentikitDefinition, ItemDefinition, NpcDefinition, ObjectDefinition,
+                OverlayDefinition, ParamDefinition, SequenceDefinition, SpotAnimDefinition,
+                VarbitDefinition,
+            },
+            error::ReadError,
+            Error,
+        };
+
+        type Decode = fn(&[u8]) -> Option<(u8, usize)>;
+
+        fn unknown<D: Definition>(buffer: &[u8]) -> Option<(u8, usize)> {
+            match D::new(1, buffer) {
+                Err(Error::Read(ReadError::UnknownOpcode { opcode, offset })) => {
+                    Some((opcode, offset))
+                }
+                _ => None,
+            }
+        }
+
+        // Each definition with a known opcode and its payload, these are followed by
+        // opcode 200 (unknown) and opcode 0 (end).
+        let cases: [(&str, Decode, &[u8]); 12] = [
+            ("area", unknown::<AreaDefinition>, &[3, b'M', b'i', 0]),
+            ("enum", unknown::<EnumDefinition>, &[1, b'i']),
+            ("hitsplat", unknown::<HitsplatDefinition>, &[2, 0xFF, 0, 0]),
+            ("identikit", unknown::<IdentikitDefinition>, &[1, 7]),
+            ("item", unknown::<ItemDefinition>, &[2, b'H', b'a', b't', 0]),
+            ("npc", unknown::<NpcDefinition>, &[2, b'M', b'a', b'n', 0]),
+            ("object", unknown::<ObjectDefinition>, &[2, b'D', b'o', 0]),
+            ("overlay", unknown::<OverlayDefinition>, &[1, 0xFF, 0, 0]),
+            ("param", unknown::<ParamDefinition>, &[1, b's']),
+            ("sequence", unknown::<SequenceDefinition>, &[5, 10]),
+            ("spot anim", unknown::<SpotAnimDefinition>, &[1, 0, 10]),
+            ("varbit", unknown::<VarbitDefinition>, &[1, 0, 5, 0, 7]),
+        ];
+
+        for (name, decode, known) in cases {
+            let buffer = [known, &[200, 0]].concat();
+
+            assert_eq!(decode(&buffer), Some((200, known.len())), "{name}");
+        }
+    }
+
+    mod items {
+        use rscache::{
+            definition::osrs::{Definition, ItemDefinition},
+            error::{DecodeWarning, ReadError},
+            Error,
+        };
+
+        #[test]
+        fn strict_decode() {
+            // opcode 2 (name), opcode 0 (end)
+            let buffer = [2, b'H', b'a', b't', 0, 0];
+            let item = ItemDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(item.name, "Hat");
+        }
+
+        #[test]
+        fn inventory_option() {
+            // opcode 9 (inventory option), opcode 0 (end)
+            let buffer = [9, b'W', b'i', b'e', b'l', b'd', 0, 0];
+            let item = ItemDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(item.inventory_option.as_deref(), Some("Wield"));
+            assert_eq!(ItemDefinition::new(1, &[0]).unwrap().inventory_option, None);
+        }
+
+        #[test]
+        fn skipped_opcode() {
+            // opcode 13 (skipped byte), opcode 2 (name), opcode 16 (members), opcode 0 (end)
+            let buffer = [13, 2, 2, b'H', b'a', b't', 0, 16, 0];
+            let item = ItemDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(item.name, "Hat");
+            assert!(item.members_only);
+        }
+
+        #[test]
+        fn untradable_alchemy() {
+            // opcode 12 (cost), opcode 0 (end)
+            let buffer = [12, 0, 0, 1, 64, 0];
+            let item = ItemDefinition::new(1, &buffer).unwrap();
+
+            assert_eq!(item.cost, 320);
+            assert_eq!(item.high_alch(), 0);
+            assert_eq!(item.low_alch(), 0);
+        }
+
+        #[test]
+        fn store_value() {
+            // opcode 12 (cost), opcode 249 (params) with integer param 1000, opcode 0 (end)
+            let buffer = [12, 0, 0, 1, 64, 249, 1, 0, 0, 3, 232, 0, 0, 1, 244, 0];
+            let item = ItemDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(item.cost, 320);
+            assert_eq!(item.store_value(1000), Some(500));
+            assert_eq!(item.store_value(1001), Some(320));
+            let free = ItemDefinition::new(1, &[0]).unwrap();
+            assert_eq!(free.store_value(1000), None);
+        }
+
+        #[test]
+        fn truncated_params() {
+            // opcode 249 (params: 2 entries but the second is cut short)
+            let buffer = [249, 2, 0, 0, 0, 1, 0, 0, 0, 5, 1, 0, 0];
+
+            assert!(matches!(
+                ItemDefinition::new(1, &buffer),
+                Err(Error::Read(ReadError::MalformedParams { entry: 1 }))
+            ));
+        }
+
+        #[test]
+        fn invalid_param_type() {
+            // opcode 249 (params: 1 entry with type byte 7)
+            let buffer = [249, 1, 7, 0, 0, 1, 0, 0, 0, 5, 0];
+
+            assert!(matches!(
+                ItemDefinition::new(1, &buffer),
+                Err(Error::Read(ReadError::MalformedParams { entry: 0 }))
+            ));
+        }
+
+        #[test]
+        fn negative_weight() {
+            // opcode 75 (weight -4500 grams), opcode 0 (end)
+            let buffer = [75, 0xEE, 0x6C, 0];
+            let item = ItemDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(item.weight, -4500);
+            assert_eq!(item.weight_kg(), -4.5);
+        }
+
+        #[test]
+        fn options_present() {
+            // opcode 30 (first option), opcode 0 (end)
+            let buffer = [30, b'E', b'a', b't', 0, 0];
+            let item = ItemDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(item.options_present(), vec!["Eat", "Take"]);
+        }
+
+        #[test]
+        fn lenient_unknown_opcode() {
+            // opcode 2 (name), opcode 200 (unknown), opcode 16 (members), opcode 0 (end)
+            let buffer = [2, b'H', b'a', b't', 0, 200, 16, 0];
+            let (item, warnings) = ItemDefinition::new_lenient(1, &buffer);
+
+            assert_eq!(item.name, "Hat");
+            assert!(!item.members_only);
+            assert_eq!(
+                warnings,
+                vec![DecodeWarning::UnknownOpcode {
+                    opcode: 200,
+                    offset: 5
+                }]
+            );
+        }
+
+        mod policy {
+            use rscache::{
+                definition::osrs::{Definition, ItemDefinition},
+                error::{DecodeWarning, ReadError},
+                loader::UnknownOpcodePolicy,
+                Error,
+            };
+
+            // opcode 2 (name), opcode 200 (unknown, 1 byte), opcode 16 (members), opcode 0 (end)
+            const BUFFER: [u8; 9] = [2, b'H', b'a', b't', 0, 200, 7, 16, 0];
+
+            #[test]
+            #[should_panic(expected = "unknown item opcode 200")]
+            fn panic() {
+                let _ = ItemDefinition::new_with_policy(1, &BUFFER, UnknownOpcodePolicy::Panic);
+            }
+
+            #[test]
+            fn error() {
+                let result =
+                    ItemDefinition::new_with_policy(1, &BUFFER, UnknownOpcodePolicy::Error);
+
+                assert!(matches!(
+                    result,
+                    Err(Error::Read(ReadError::UnknownOpcode {
+                        opcode: 200,
+                        offset: 5
+                    }))
+                ));
+            }
+
+            #[test]
+            fn skip() {
+                let (item, warnings) =
+                    ItemDefinition::new_with_policy(1, &BUFFER, UnknownOpcodePolicy::Skip(1))
+                        .unwrap();
+
+                assert_eq!(item.name, "Hat");
+                assert!(item.members_only);
+                assert!(warnings.is_empty());
+            }
+
+            #[test]
+            fn warn() {
+                let (item, warnings) =
+                    ItemDefinition::new_with_policy(1, &BUFFER, UnknownOpcodePolicy::Warn).unwrap();
+
+                assert_eq!(item.name, "Hat");
+                assert!(!item.members_only);
+                assert_eq!(
+                    warnings,
+                    vec![DecodeWarning::UnknownOpcode {
+                        opcode: 200,
+                        offset: 5
+                    }]
+                );
+            }
+        }
+
+        #[test]
+        fn lenient_truncated() {
+            // opcode 2 (name), opcode 12 (cost cut short)
+            let buffer = [2, b'H', b'a', b't', 0, 12, 0];
+            let (item, warnings) = ItemDefinition::new_lenient(1, &buffer);
+
+            assert_eq!(item.name, "Hat");
+            assert!(matches!(warnings[..], [DecodeWarning::Stopped(_)]));
+
+            let (item, warnings) = ItemDefinition::new_lenient(1, &[2, b'H', b'a', b't', 0, 0]);
+            assert_eq!(item.name, "Hat");
+            assert!(warnings.is_empty());
+        }
+
+        #[test]
+        fn model_defaults() {
+            // opcode 2 (name), opcode 0 (end)
+            let buffer = [2, b'H', b'a', b't', 0, 0];
+            let model_data = ItemDefinition::new_strict(1, &buffer)
+                .unwrap()
+                .inventory_model_data;
+
+            // Renderers rely on these defaults when the opcodes are absent.
+            assert_eq!(model_data.zoom2d, 2000);
+            assert_eq!(model_data.resize_x, 128);
+            assert_eq!(model_data.resize_y, 128);
+            assert_eq!(model_data.resize_z, 128);
+        }
+
+        #[test]
+        fn model_defaults_overridden() {
+            // opcode 4 (zoom), opcode 110 (resize x), opcode 111 (resize y),
+            // opcode 112 (resize z), opcode 0 (end)
+            let buffer = [4, 3, 232, 110, 0, 64, 111, 1, 0, 112, 0, 128, 0];
+            let model_data = ItemDefinition::new_strict(1, &buffer)
+                .unwrap()
+                .inventory_model_data;
+
+            assert_eq!(model_data.zoom2d, 1000);
+            assert_eq!(model_data.resize_x, 64);
+            assert_eq!(model_data.resize_y, 256);
+            assert_eq!(model_data.resize_z, 128);
+        }
+
+        #[test]
+        fn gameplay_eq() {
+            // opcode 1 (inventory model), opcode 4 (zoom), opcode 2 (name), opcode 12 (cost),
+            // opcode 0 (end)
+            let hat = [
+                1, 0, 10, 4, 0, 20, 2, b'H', b'a', b't', 0, 12, 0, 0, 0, 5, 0,
+            ];
+            let remodelled_hat = [
+                1, 0, 11, 4, 0, 30, 2, b'H', b'a', b't', 0, 12, 0, 0, 0, 5, 0,
+            ];
+            let pricier_hat = [
+                1, 0, 10, 4, 0, 20, 2, b'H', b'a', b't', 0, 12, 0, 0, 0, 6, 0,
+            ];
+
+            let hat = ItemDefinition::new_strict(1, &hat).unwrap();
+            let remodelled_hat = ItemDefinition::new_strict(1, &remodelled_hat).unwrap();
+            let pricier_hat = ItemDefinition::new_strict(1, &pricier_hat).unwrap();
+
+            assert_ne!(hat, remodelled_hat);
+            assert!(hat.gameplay_eq(&remodelled_hat));
+            assert!(!hat.gameplay_eq(&pricier_hat));
+        }
+
+        #[test]
+        fn strict_trailing_bytes() {
+            let buffer = [2, b'H', b'a', b't', 0, 0, 255, 255];
+
+            assert!(ItemDefinition::new(1, &buffer).is_ok());
+            assert!(matches!(
+                ItemDefinition::new_strict(1, &buffer),
+                Err(Error::Read(ReadError::TrailingBytes { remaining: 2 }))
+            ));
+        }
+    }
+
+    mod raw {
+        use rscache::{
+            definition::osrs::{Definition, ItemDefinition, RawDefinition},
+            loader::UnknownOpcodePolicy,
+        };
+
+        #[test]
+        fn retains_input() {
+            // opcode 2 (name), opcode 16 (members), opcode 0 (end)
+            let buffer = [2, b'H', b'a', b't', 0, 16, 0];
+            let item = RawDefinition::<ItemDefinition>::new(1, &buffer).unwrap();
+
+            assert_eq!(item.raw(), &buffer);
+            assert_eq!(item.definition, ItemDefinition::new(1, &buffer).unwrap());
+        }
+
+        #[test]
+        fn strict_trailing_bytes() {
+            let buffer = [2, b'H', b'a', b't', 0, 0, 1];
+
+            assert!(RawDefinition::<ItemDefinition>::new_strict(1, &buffer).is_err());
+        }
+
+        #[test]
+        fn lenient_and_policy() {
+            // opcode 2 (name), opcode 200 (unknown, 1 byte), opcode 16 (members), opcode 0 (end)
+            let buffer = [2, b'H', b'a', b't', 0, 200, 7, 16, 0];
+
+            let (item, warnings) = RawDefinition::<ItemDefinition>::new_lenient(1, &buffer);
+            assert_eq!(item.raw(), &buffer);
+            assert_eq!(item.definition.name, "Hat");
+            assert_eq!(warnings.len(), 1);
+
+            let (item, warnings) = RawDefinition::<ItemDefinition>::new_with_policy(
+                1,
+                &buffer,
+                UnknownOpcodePolicy::Skip(1),
+            )
+            .unwrap();
+            assert_eq!(item.raw(), &buffer);
+            assert!(item.definition.members_only);
+            assert!(warnings.is_empty());
+        }
+    }
+
+    mod areas {
+        use rscache::definition::osrs::{AreaDefinition, Definition};
+
+        #[test]
+        fn decode() {
+            // opcode 3 (name), opcode 15 (triangle outline), opcode 19 (category 1129),
+            // opcode 0 (end)
+            let buffer = [
+                3, b'M', b'i', b'n', b'e', 0, 15, 3, 0, 0, 0, 0, 0, 10, 0, 0, 0, 5, 255, 246, 0, 0,
+                0, 0, 0, 1, 2, 3, 19, 4, 105, 0,
+            ];
+            let area = AreaDefinition::new(1, &buffer).unwrap();
+
+            assert_eq!(area.name, "Mine");
+            assert_eq!(area.polygon, vec![(0, 0), (10, 0), (5, -10)]);
+            assert_eq!(area.category, 1129);
+        }
+    }
+
+    mod enums {
+        use rscache::definition::osrs::{Definition, EnumDefinition};
+
+        #[test]
+        fn decode() {
+            // opcode 1 (key type 'i'), opcode 2 (value type 's'), opcode 5 (1 string value),
+            // opcode 0 (end)
+            let mut buffer = vec![1, b'i', 2, b's', 5, 0, 1, 0, 0, 0, 6];
+            buffer.extend_from_slice(b"Harmony\0");
+            buffer.push(0);
+            let enum_def = EnumDefinition::new_strict(812, &buffer).unwrap();
+
+            assert_eq!(enum_def.key_type, b'i');
+            assert_eq!(enum_def.value_type, b's');
+            assert_eq!(enum_def.string_value(6), "Harmony");
+            assert_eq!(enum_def.string_value(7), "null");
+        }
+
+        #[test]
+        fn int_values() {
+            // opcode 4 (default -1), opcode 6 (1 int value), opcode 0 (end)
+            let buffer = [4, 255, 255, 255, 255, 6, 0, 1, 0, 0, 0, 3, 0, 0, 1, 0, 0];
+            let enum_def = EnumDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(enum_def.int_value(3), 256);
+            assert_eq!(enum_def.int_value(4), -1);
+        }
+    }
+
+    mod sequences {
+        use rscache::definition::osrs::{Definition, SequenceDefinition};
+
+        #[test]
+        fn decode() {
+            // opcode 1 (2 frames), opcode 10 (priority 1), opcode 0 (end)
+            let buffer = [1, 0, 2, 0, 3, 0, 4, 0, 1, 0, 2, 3, 243, 3, 243, 10, 1, 0];
+            let sequence = SequenceDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(sequence.frame_lengths, vec![3, 4]);
+            assert_eq!(sequence.frame_ids, vec![0x3F3_0001, 0x3F3_0002]);
+            assert_eq!(sequence.duration(), 7);
+            assert_eq!(sequence.priority, Some(1));
+            assert_eq!(sequence.max_loops, 99);
+        }
+    }
+
+    mod frames {
+        use rscache::definition::osrs::{
+            Definition, FrameDefinition, FrameTransform, SkeletonDefinition,
+        };
+
+        #[test]
+        fn decode() {
+            // 3 slots: origin, rotation and scale, every slot moves vertex group 0
+            let skeleton = SkeletonDefinition::new(7, &[3, 0, 2, 3, 1, 1, 1, 0, 0, 0]).unwrap();
+            // skeleton 7, 3 flags: rotate y (10) and scale x (10)
+            let buffer = [0, 7, 3, 0, 2, 1, 74, 74];
+            let frame = FrameDefinition::new(1, &buffer, &skeleton).unwrap();
+
+            assert_eq!(frame.skeleton_id, 7);
+            assert_eq!(
+                frame.transforms,
+                vec![
+                    // The origin the rotation needs is reset first.
+                    FrameTransform {
+                        slot: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0
+                    },
+                    FrameTransform {
+                        slot: 1,
+                        x: 0,
+                        y: 10,
+                        z: 0
+                    },
+                    FrameTransform {
+                        slot: 2,
+                        x: 10,
+                        y: 128,
+                        z: 128
+                    },
+                ]
+            );
+        }
+    }
+
+    mod spot_anims {
+        use rscache::definition::osrs::{Definition, SpotAnimDefinition};
+
+        #[test]
+        fn decode() {
+            // opcode 1 (model 3081), opcode 2 (animation 659), opcode 7 (ambient 60),
+            // opcode 0 (end)
+            let buffer = [1, 12, 9, 2, 2, 147, 7, 60, 0];
+            let spot_anim = SpotAnimDefinition::new_strict(100, &buffer).unwrap();
+
+            assert_eq!(spot_anim.model_id, 3081);
+            assert_eq!(spot_anim.animation_id, Some(659));
+            assert_eq!(spot_anim.ambient, 60);
+            assert_eq!(spot_anim.resize_x, 128);
+        }
+    }
+
+    mod hitsplats {
+        use rscache::definition::osrs::{Definition, HitsplatDefinition};
+
+        #[test]
+        fn decode() {
+            // opcode 8 (format "%1"), opcode 5 (middle sprite 1358), opcode 2 (text color),
+            // opcode 9 (display cycles 50), opcode 0 (end)
+            let buffer = [
+                8, 0, b'%', b'1', 0, 5, 5, 78, 2, 0xFF, 0x00, 0x00, 9, 0, 50, 0,
+            ];
+            let hitsplat = HitsplatDefinition::new(1, &buffer).unwrap();
+
+            assert_eq!(hitsplat.middle_sprite_id, Some(1358));
+            assert_eq!(hitsplat.left_sprite_id, None);
+            assert_eq!(hitsplat.text_color, 0xFF0000);
+            assert_eq!(hitsplat.display_cycles, 50);
+            assert_eq!(hitsplat.format_damage(12), "12");
+        }
+
+        #[test]
+        fn defaults() {
+            let hitsplat = HitsplatDefinition::new(1, &[0]).unwrap();
+
+            assert_eq!(hitsplat.text_color, 0xFFFFFF);
+            assert_eq!(hitsplat.display_cycles, 70);
+            assert_eq!(hitsplat.fade_start_cycle, None);
+        }
+    }
+
+    mod identikits {
+        use rscache::definition::osrs::{Definition, IdentikitDefinition};
+
+        #[test]
+        fn decode() {
+            // opcode 1 (body part 7), opcode 2 (models 230 and 231), opcode 3 (non selectable),
+            // opcode 60 (head model 63), opcode 0 (end)
+            let buffer = [1, 7, 2, 2, 0, 230, 0, 231, 3, 60, 0, 63, 0];
+            let kit = IdentikitDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(kit.body_part, Some(7));
+            assert!(kit.female());
+            assert_eq!(kit.models, vec![230, 231]);
+            assert_eq!(kit.head_models, [Some(63), None, None, None, None]);
+            assert!(!kit.selectable());
+        }
+    }
+
+    mod npcs {
+        use rscache::{
+            definition::osrs::{Definition, NpcDefinition, NpcHeadIcon},
+            error::{DecodeWarning, ReadError},
+            loader::{LoaderOptions, UnknownOpcodePolicy},
+            Error,
+        };
+
+        #[test]
+        fn revision() {
+            // opcode 102 (head icon 0 before revision 210, an empty bitfield since), opcode 0 (end)
+            let buffer = [102, 0, 0, 0];
+            let options = |revision| LoaderOptions {
+                revision: Some(revision),
+                ..LoaderOptions::default()
+            };
+
+            let (npc, _) = NpcDefinition::new_with_options(1, &buffer, options(209)).unwrap();
+            assert_eq!(npc.model_data.head_icon, Some(0));
+
+            let (npc, _) = NpcDefinition::new_with_options(1, &buffer, options(210)).unwrap();
+            assert_eq!(npc.model_data.head_icon, None);
+            assert!(npc.model_data.head_icons.is_empty());
+
+            // Without a revision the current layout is used and the last byte is left over.
+            assert!(matches!(
+                NpcDefinition::new_strict(1, &buffer),
+                Err(Error::Read(ReadError::TrailingBytes { remaining: 1 }))
+            ));
+        }
+
+        #[test]
+        fn unknown_opcode_policy() {
+            // opcode 2 (name), opcode 200 (unknown, 1 byte), opcode 12 (size 2), opcode 0 (end)
+            let buffer = [2, b'M', b'a', b'n', 0, 200, 7, 12, 2, 0];
+
+            let (npc, warnings) =
+                NpcDefinition::new_with_policy(1, &buffer, UnknownOpcodePolicy::Skip(1)).unwrap();
+            assert_eq!(npc.name, "Man");
+            assert_eq!(npc.size, 2);
+            assert!(warnings.is_empty());
+
+            let (npc, warnings) =
+                NpcDefinition::new_with_policy(1, &buffer, UnknownOpcodePolicy::Warn).unwrap();
+            assert_eq!(npc.name, "Man");
+            assert_eq!(npc.size, NpcDefinition::new(1, &[0]).unwrap().size);
+            assert_eq!(
+                warnings,
+                vec![DecodeWarning::UnknownOpcode {
+                    opcode: 200,
+                    offset: 5
+                }]
+            );
+
+            assert!(matches!(
+                NpcDefinition::new_with_policy(1, &buffer, UnknownOpcodePolicy::default()),
+                Err(Error::Read(ReadError::UnknownOpcode {
+                    opcode: 200,
+                    offset: 5
+                }))
+            ));
+        }
+
+        #[test]
+        #[should_panic(expected = "unknown npc opcode 200")]
+        fn unknown_opcode_panic() {
+            let buffer = [2, b'M', b'a', b'n', 0, 200, 0];
+            let _ = NpcDefinition::new_with_policy(1, &buffer, UnknownOpcodePolicy::Panic);
+        }
+
+        #[test]
+        fn actions_present() {
+            // opcode 30 (first action), opcode 32 (third action), opcode 0 (end)
+            let buffer = [
+                30, b'T', b'a', b'l', b'k', b'-', b't', b'o', 0, 32, b'T', b'r', b'a', b'd', b'e',
+                0, 0,
+            ];
+            let npc = NpcDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(npc.actions_present(), vec!["Talk-to", "Trade"]);
+            assert!(NpcDefinition::new_strict(2, &[0])
+                .unwrap()
+                .actions_present()
+                .is_empty());
+        }
+
+        #[test]
+        fn head_icons() {
+            // opcode 102 (slots 0 and 2: archive 300 frame 2, archive 5 frame 4), opcode 0 (end)
+            let buffer = [102, 0b101, 0x01, 0x2C, 3, 0x00, 0x05, 0x80, 0x05, 0];
+            let npc = NpcDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(
+                npc.model_data.head_icons,
+                vec![
+                    Some(NpcHeadIcon {
+                        archive_id: 300,
+                        sprite_index: 2
+                    }),
+                    None,
+                    Some(NpcHeadIcon {
+                        archive_id: 5,
+                        sprite_index: 4
+                    }),
+                ]
+            );
+        }
+
+        #[test]
+        fn footprint() {
+            // opcode 12 (size 2), opcode 0 (end)
+            let npc = NpcDefinition::new_strict(1, &[12, 2, 0]).unwrap();
+
+            assert_eq!(
+                npc.footprint((3200, 3200)),
+                vec![(3200, 3200), (3201, 3200), (3200, 3201), (3201, 3201)]
+            );
+            assert_eq!(
+                NpcDefinition::new_strict(2, &[0])
+                    .unwrap()
+                    .footprint((-1, 5)),
+                vec![(-1, 5)]
+            );
+        }
+
+        #[test]
+        fn chathead() {
+            // opcode 60 (chathead models), opcode 0 (end)
+            let buffer = [60, 2, 0, 10, 0, 11, 0];
+            let npc = NpcDefinition::new(1, &buffer).unwrap();
+
+            assert!(npc.has_chathead());
+            assert_eq!(npc.chathead_models(), &[10, 11]);
+        }
+
+        #[test]
+        fn recolor_pairs() {
+            // opcode 40 (recolor 10 => 20, 11 => 21), opcode 0 (end)
+            let buffer = [40, 2, 0, 10, 0, 20, 0, 11, 0, 21, 0];
+            let npc = NpcDefinition::new_strict(1, &buffer).unwrap();
+            let pairs: Vec<(u16, u16)> = npc.model_data.recolor_pairs().collect();
+
+            assert_eq!(pairs, vec![(10, 20), (11, 21)]);
+        }
+
+        #[test]
+        fn no_chathead() {
+            // opcode 2 (name), opcode 0 (end)
+            let buffer = [2, b'R', b'a', b't', 0, 0];
+            let npc = NpcDefinition::new(1, &buffer).unwrap();
+
+            assert!(!npc.has_chathead());
+            assert!(npc.chathead_models().is_empty());
+        }
+    }
+
+    mod objects {
+        use rscache::{
+            definition::osrs::{Definition, ObjectDefinition, ObjectType},
+            error::DecodeWarning,
+        };
+
+        #[test]
+        fn lenient_truncated() {
+            // opcode 2 (name), opcode 24 (animation cut short)
+            let buffer = [2, b'D', b'o', b'o', b'r', 0, 24, 8];
+            let (obj, warnings) = ObjectDefinition::new_lenient(1, &buffer);
+
+            assert_eq!(obj.name, "Door");
+            assert_eq!(obj.animation_id, None);
+            assert!(matches!(warnings[..], [DecodeWarning::Stopped(_)]));
+        }
+
+        #[test]
+        fn animated() {
+            // opcode 24 (animation), opcode 0 (end)
+            let buffer = [24, 8, 130, 0];
+            let obj = ObjectDefinition::new_strict(1, &buffer).unwrap();
+
+            assert!(obj.supports_animation());
+            assert_eq!(obj.animation_id, Some(2178));
+        }
+
+        #[test]
+        fn no_animation() {
+            // opcode 24 (no animation), opcode 0 (end)
+            let buffer = [24, 255, 255, 0];
+            let obj = ObjectDefinition::new_strict(1, &buffer).unwrap();
+
+            assert!(!obj.supports_animation());
+            assert_eq!(obj.animation_id, None);
+        }
+
+        #[test]
+        fn rendering_flags() {
+            // opcode 21 (contoured ground), opcode 23 (delay shading), opcode 62 (mirror),
+            // opcode 73 (obstructs ground), opcode 0 (end)
+            let buffer = [21, 23, 62, 73, 0];
+            let obj = ObjectDefinition::new_strict(1, &buffer).unwrap();
+
+            assert!(obj.contours_ground());
+            assert_eq!(obj.contoured_ground, Some(0));
+            assert!(obj.delay_shading);
+            assert!(obj.mirror());
+            assert!(obj.obstruct_ground);
+        }
+
+        #[test]
+        fn wall_decoration() {
+            // opcode 1 (model 1234 as shape 4), opcode 28 (decor displacement),
+            // opcodes 70 to 72 (offsets), opcode 0 (end)
+            let models = [1, 1, 4, 210, 4];
+            let displacement = [28, 24];
+            let offsets = [70, 0xFF, 0xF0, 71, 0, 8, 72, 0x80, 0];
+            let buffer = [&models[..], &displacement, &offsets, &[0]].concat();
+            let obj = ObjectDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(obj.object_type(), ObjectType::WallDecoration);
+            assert_eq!(obj.model_data.decor_displacement, 24);
+            assert_eq!(obj.model_data.offset_x, -16);
+            assert_eq!(obj.model_data.offset_z, 8);
+            assert_eq!(obj.model_data.offset_y, i16::MIN);
+
+            let obj = ObjectDefinition::new_strict(1, &[0]).unwrap();
+            assert_eq!(obj.model_data.decor_displacement, 16);
+        }
+
+        #[test]
+        fn no_rendering_flags() {
+            let obj = ObjectDefinition::new_strict(1, &[0]).unwrap();
+
+            assert!(!obj.contours_ground());
+            assert!(!obj.delay_shading);
+            assert!(!obj.mirror());
+            assert!(!obj.obstruct_ground);
+        }
+
+        #[test]
+        fn map_icons() {
+            // opcode 2 (name "Bank booth"), opcode 68 (map scene 24), opcode 82 (map area 5),
+            // opcode 0 (end)
+            let mut buffer = vec![2];
+            buffer.extend_from_slice(b"Bank booth\0");
+            buffer.extend_from_slice(&[68, 0, 24, 82, 0, 5, 0]);
+            let obj = ObjectDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(obj.name, "Bank booth");
+            assert_eq!(obj.map_scene_id, Some(24));
+            assert_eq!(obj.map_area_id, Some(5));
+        }
+
+        #[test]
+        fn no_map_icons() {
+            let obj = ObjectDefinition::new_strict(1, &[0]).unwrap();
+
+            assert_eq!(obj.map_scene_id, None);
+            assert_eq!(obj.map_area_id, None);
+        }
+
+        #[test]
+        fn ambient_sound() {
+            // opcode 78 (sound 2748, distance 5, retain 1), opcode 0 (end)
+            let buffer = [78, 10, 188, 5, 1, 0];
+            let obj = ObjectDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(obj.ambient_sound_id, Some(2748));
+            assert_eq!(obj.ambient_sound_distance, 5);
+            assert_eq!(obj.ambient_sound_retain, 1);
+            assert!(obj.ambient_sound_ids.is_empty());
+        }
+
+        #[test]
+        fn random_ambient_sounds() {
+            // opcode 79 (every 10 to 20 ticks, distance 3, retain 0, sounds 100 and 101),
+            // opcode 0 (end)
+            let buffer = [79, 0, 10, 0, 20, 3, 0, 2, 0, 100, 0, 101, 0];
+            let obj = ObjectDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(obj.ambient_sound_id, None);
+            assert_eq!(obj.ambient_sound_change_ticks, (10, 20));
+            assert_eq!(obj.ambient_sound_distance, 3);
+            assert_eq!(obj.ambient_sound_ids, vec![100, 101]);
+        }
+
+        #[test]
+        fn wall() {
+            // opcode 1 (model 10 with shape 0), opcode 0 (end)
+            let buffer = [1, 1, 0, 10, 0, 0];
+            let obj = ObjectDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(obj.object_type(), ObjectType::Wall);
+        }
+
+        #[test]
+        fn ground_scenery() {
+            // opcode 1 (model 10 with shape 10), opcode 0 (end)
+            let buffer = [1, 1, 0, 10, 10, 0];
+            let obj = ObjectDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(obj.object_type(), ObjectType::Scenery);
+            assert_eq!(ObjectType::from_shape(22), ObjectType::GroundDecoration);
+        }
+
+        #[test]
+        fn params() {
+            // opcode 2 (name), opcode 249 (params: int 1 => 5, string 2 => "ash"), opcode 0 (end)
+            let buffer = [
+                2, b'T', b'r', b'e', b'e', 0, 249, 2, 0, 0, 0, 1, 0, 0, 0, 5, 1, 0, 0, 2, b'a',
+                b's', b'h', 0, 0,
+            ];
+            let obj = ObjectDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(obj.name, "Tree");
+            assert_eq!(obj.params.len(), 2);
+            assert_eq!(obj.params[&1], "5");
+            assert_eq!(obj.params[&2], "ash");
+        }
+    }
+}
+
+#[cfg(feature = "rs3")]
+mod rs3 {
+    mod items {
+        use rscache::{
+            definition::rs3::{Definition, ItemDefinition},
+            error::ReadError,
+            Error,
+        };
+
+        #[test]
+        fn decode() {
+            // opcode 41 (retexture 10 to 20), opcodes 101 and 102 (stacks), opcode 127 (cursor),
+            // opcode 249 (integer param 1), opcode 13 (weapon slot), opcode 14 (hides shield),
+            // opcode 0 (end)
+            let buffer = [
+                41, 1, 0, 10, 0, 20, 101, 0, 5, 0, 6, 102, 0, 7, 0, 8, 127, 2, 0, 44, 249, 1, 0, 0,
+                0, 1, 0, 0, 0, 9, 13, 3, 14, 5, 0,
+            ];
+            let item = ItemDefinition::new_strict(1, &buffer).unwrap();
+
+            assert_eq!(item.model_data.original_texture_colors, vec![10]);
+            assert_eq!(item.model_data.modified_texture_colors, vec![20]);
+            assert_eq!(item.stack_ids.unwrap()[1..3], [5, 7]);
+            assert_eq!(item.stack_count.unwrap()[1..3], [6, 8]);
+            assert_eq!(item.cursors[0], Some((2, 44)));
+            assert_eq!(item.params[&1], "9");
+            assert_eq!(item.model_data.resize_x, 128);
+            assert!(item.two_handed());
+        }
+
+        #[test]
+        fn unknown_opcode() {
+            // opcode 2 (name), opcode 200 (unknown), opcode 0 (end)
+            let buffer = [2, b'H', b'a', b't', 0, 200, 0];
+
+            assert!(matches!(
+                ItemDefinition::new(1, &buffer),
+                Err(Error::Read(ReadError::UnknownOpcode {
+                    opcode: 200,
+                    offset: 5
+                }))
+            ));
+        }
+    }
+
+    mod sprites {
+        use rscache::definition::rs3::{Definition, SpriteDefinition};
+
+        #[test]
+        fn true_color() {
+            // format 0, alpha flag, 2x1 pixels, 2 rgb pixels, 2 alphas, frame count with the high bit set
+            let buffer = [
+                0, 1, 0, 2, 0, 1, 0xFF, 0, 0, 0, 0x80, 0xFF, 0x80, 0x00, 0x80, 0x01,
+            ];
+            let sprite = SpriteDefinition::new(1, &buffer).unwrap();
+
+            assert_eq!(sprite.frame_count(), 1);
+            assert_eq!((sprite.max_width, sprite.max_height), (2, 1));
+            assert_eq!(sprite.frames[0].pixels, vec![0x80FF_0000, 0x0000_80FF]);
+        }
+
+        #[test]
+        fn paletted() {
+            // 1x1 pixel with palette index 1, palette [0x00FF00], header, frame count 1
+            let buffer = [
+                0, 1, 0, 0xFF, 0, 0, 1, 0, 1, 1, 0, 0, 0, 0, 0, 1, 0, 1, 0, 1,
+            ];
+            let sprite = SpriteDefinition::new(2, &buffer).unwrap();
+
+            assert_eq!(sprite.frame_count(), 1);
+            assert_eq!(sprite.frames[0].pixels, vec![0xFF00_FF00]);
+        }
+    }
+}
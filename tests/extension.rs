@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use rscache::extension::{PayloadSize, ReadExt};
+
+#[test]
+fn read_rgb() {
+    let mut buffer: &[u8] = &[0xFF, 0x80, 0x01, 0x00];
+
+    assert_eq!(buffer.read_rgb().unwrap(), (0xFF, 0x80, 0x01));
+    assert_eq!(buffer.len(), 1);
+}
+
+#[test]
+fn read_string() {
+    let mut buffer: &[u8] = &[b'H', b'a', b't', 0, 0xA3, b'5', 0, 0xE9, 0x80, 0x99, 0];
+
+    assert_eq!(buffer.read_string().unwrap(), "Hat");
+    assert_eq!(buffer.read_string().unwrap(), "£5");
+    assert_eq!(buffer.read_string().unwrap(), "é€™");
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn skip() {
+    let mut buffer: &[u8] = &[1, 2, 3, b'H', b'a', b't', 0, 4, 5];
+
+    buffer.skip(2).unwrap();
+    assert_eq!(buffer.read_u8().unwrap(), 3);
+    buffer.skip_string().unwrap();
+    assert_eq!(buffer.read_u8().unwrap(), 4);
+    assert!(buffer.skip(2).is_err());
+    assert!(buffer.skip_string().is_err());
+}
+
+#[test]
+fn read_bytes() {
+    let mut buffer: &[u8] = &[1, 2, 3, 4, 5];
+
+    assert_eq!(buffer.read_bytes(4).unwrap(), vec![1, 2, 3, 4]);
+    assert!(buffer.read_bytes(2).is_err());
+}
+
+#[test]
+fn read_bytes_into() {
+    let mut buffer: &[u8] = &[1, 2, 3, 4, 5];
+    let mut blob = [0; 4];
+
+    buffer.read_bytes_into(&mut blob).unwrap();
+    assert_eq!(blob, [1, 2, 3, 4]);
+    assert_eq!(buffer, &[5]);
+}
+
+#[test]
+fn read_smart_i16() {
+    let mut buffer: &[u8] = &[0, 64, 127, 0x80, 0x00, 0xFF, 0xFF];
+
+    assert_eq!(buffer.read_smart_i16().unwrap(), -64);
+    assert_eq!(buffer.read_smart_i16().unwrap(), 0);
+    assert_eq!(buffer.read_smart_i16().unwrap(), 63);
+    assert_eq!(buffer.read_smart_i16().unwrap(), -16384);
+    assert_eq!(buffer.read_smart_i16().unwrap(), 16383);
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn read_until_opcode_zero() {
+    let sizes = HashMap::from([
+        (1, PayloadSize::Fixed(2)),
+        (2, PayloadSize::String),
+        (40, PayloadSize::Counted(4)),
+    ]);
+    // opcode 1 (u16), opcode 2 (string), opcode 40 (one recolor), opcode 0 (end), next definition
+    let mut buffer: &[u8] = &[1, 0, 5, 2, b'H', b'i', 0, 40, 1, 0, 1, 0, 2, 0, 7];
+
+    assert_eq!(
+        buffer.read_until_opcode_zero(&sizes).unwrap(),
+        vec![
+            (1, vec![0, 5]),
+            (2, vec![b'H', b'i', 0]),
+            (40, vec![1, 0, 1, 0, 2]),
+        ]
+    );
+    assert_eq!(buffer, &[7]);
+}
+
+#[test]
+fn read_until_opcode_zero_params() {
+    let sizes = HashMap::from([(249, PayloadSize::Params)]);
+    // opcode 249 (an int and a string parameter), opcode 0 (end), next definition
+    let params = [2, 0, 0, 0, 1, 0, 0, 0, 9, 1, 0, 0, 2, b'H', b'i', 0];
+    let mut buffer: &[u8] = &[&[249][..], &params, &[0, 7]].concat();
+
+    assert_eq!(
+        buffer.read_until_opcode_zero(&sizes).unwrap(),
+        vec![(249, params.to_vec())]
+    );
+    assert_eq!(buffer, &[7]);
+}
+
+#[test]
+fn read_until_unknown_opcode() {
+    let sizes = HashMap::from([(1, PayloadSize::Fixed(2))]);
+    let mut buffer: &[u8] = &[1, 0, 5, 99, 3, 0, 4];
+
+    assert_eq!(
+        buffer.read_until_opcode_zero(&sizes).unwrap(),
+        vec![(1, vec![0, 5]), (99, vec![3, 0, 4])]
+    );
+    assert!(buffer.is_empty());
+}
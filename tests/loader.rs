@@ -12,6 +12,140 @@ mod osrs {
             ItemLoader::new(&test_util::osrs_cache()).unwrap()
         }
 
+        #[test]
+        fn free_to_play() {
+            let item_loader = item_loader();
+            let f2p: Vec<u16> = item_loader.free_to_play().map(|item| item.id).collect();
+            let members = item_loader.members().count();
+
+            // Abyssal whip and its noted variant.
+            assert!(!f2p.contains(&4151));
+            assert!(!f2p.contains(&4152));
+            // Bronze sword.
+            assert!(f2p.contains(&1277));
+            assert_eq!(f2p.len() + members, item_loader.len());
+        }
+
+        #[test]
+        fn canonical() {
+            let item_loader = item_loader();
+
+            // Abyssal whip, its noted variant and its bank placeholder.
+            assert_eq!(item_loader.canonical(4151).unwrap().id, 4151);
+            assert_eq!(item_loader.canonical(4152).unwrap().id, 4151);
+            assert_eq!(item_loader.canonical(14032).unwrap().id, 4151);
+            assert_eq!(item_loader.canonical(14032).unwrap().name, "Abyssal whip");
+            assert!(item_loader.canonical(u16::MAX).is_none());
+        }
+
+        #[test]
+        fn new_with_report() {
+            let (item_loader, report) =
+                ItemLoader::new_with_report(&test_util::osrs_cache()).unwrap();
+
+            assert_eq!(report.loaded, item_loader.len());
+            assert_eq!(report.loaded + report.skipped + report.errors.len(), 23458);
+            assert!(report.errors.is_empty());
+            assert!(report.elapsed > std::time::Duration::ZERO);
+            assert_eq!(item_loader, self::item_loader());
+        }
+
+        #[test]
+        fn new_with_report_failures() {
+            use rscache::{
+                codec::{self, Compression},
+                definition::osrs::{FetchDefinition, ItemDefinition, RawDefinition},
+                Cache,
+            };
+            use std::collections::HashMap;
+
+            let base = test_util::osrs_cache();
+            let raw_items: HashMap<u16, RawDefinition<ItemDefinition>> =
+                ItemDefinition::fetch_from_archive(&base, 2, 10).unwrap();
+            let mut files: Vec<Vec<u8>> =
+                (0..23458).map(|id| raw_items[&id].raw().to_vec()).collect();
+
+            // An empty item, and an item with opcode 200 (unknown) after its name.
+            files[0].clear();
+            files[1] = vec![2, b'H', b'a', b't', 0, 200, 0];
+
+            // A single chunk: the files, the size delta of every file and the chunk count.
+            let mut group = files.concat();
+            let mut previous_len = 0;
+            for file in &files {
+                group.extend_from_slice(&(file.len() as i32 - previous_len).to_be_bytes());
+                previous_len = file.len() as i32;
+            }
+            group.push(1);
+
+            let archive = codec::encode(Compression::None, &group, None).unwrap();
+            let overlay = test_util::write_sparse_cache("item_report", &[(2, 10, &archive)]);
+            let cache = Cache::with_overlay("./data/osrs_cache", overlay).unwrap();
+            let (item_loader, report) = ItemLoader::new_with_report(&cache).unwrap();
+
+            assert_eq!(report.skipped, 1);
+            assert_eq!(report.errors.len(), 1);
+            assert_eq!(report.errors[0].0, 1);
+            assert_eq!(report.loaded, 23456);
+            assert!(item_loader.load(1).is_none());
+            assert_eq!(item_loader.load(4151).unwrap().name, "Abyssal whip");
+        }
+
+        #[test]
+        fn new_with_options() {
+            use rscache::loader::{LoaderOptions, UnknownOpcodePolicy};
+
+            let options = LoaderOptions {
+                on_unknown_opcode: UnknownOpcodePolicy::Error,
+                ..LoaderOptions::default()
+            };
+            let (item_loader, report) =
+                ItemLoader::new_with_options(&test_util::osrs_cache(), options).unwrap();
+
+            assert!(report.errors.is_empty());
+            assert!(report.warnings.is_empty());
+            assert_eq!(item_loader, self::item_loader());
+        }
+
+        #[test]
+        fn len() {
+            let item_loader = item_loader();
+
+            // Every file of the item archive holds one item.
+            assert_eq!(item_loader.len(), 23458);
+            assert!(!item_loader.is_empty());
+            assert_eq!(item_loader[1042].name, "Blue partyhat");
+            assert_eq!(item_loader.iter().count(), item_loader.len());
+        }
+
+        #[test]
+        fn index() {
+            let item_loader = item_loader();
+
+            assert_eq!(&item_loader[4151], item_loader.load(4151).unwrap());
+        }
+
+        #[test]
+        #[should_panic]
+        fn index_missing() {
+            let _ = &item_loader()[u16::MAX];
+        }
+
+        #[test]
+        fn resolve_noted_fields() {
+            let item_loader = item_loader();
+            let whip = item_loader.load(4151).unwrap();
+            let noted = item_loader.resolve_noted_fields(4152).unwrap();
+            let template = item_loader.load(799).unwrap();
+
+            assert_eq!(item_loader.load(4152).unwrap().name, "");
+            assert_eq!(noted.name, "Abyssal whip");
+            assert_eq!(noted.cost, whip.cost);
+            assert!(noted.stackable);
+            assert_eq!(noted.inventory_model_data, template.inventory_model_data);
+            assert_eq!(item_loader.resolve_noted_fields(4151).as_ref(), Some(whip));
+        }
+
         #[test]
         fn blue_partyhat() {
             let item_loader = item_loader();
@@ -22,6 +156,16 @@ mod osrs {
             assert!(!item.members_only);
         }
 
+        #[test]
+        fn recolor_pairs() {
+            let item_loader = item_loader();
+            let model_data = &item_loader.load(1042).unwrap().inventory_model_data;
+            let pairs: Vec<(u16, u16)> = model_data.recolor_pairs().collect();
+
+            assert_eq!(pairs.len(), model_data.color_find.len());
+            assert_eq!(pairs, vec![(926, 43968)]);
+        }
+
         #[test]
         fn magic_logs() {
             let item_loader = item_loader();
@@ -32,6 +176,58 @@ mod osrs {
             assert!(item.members_only);
         }
 
+        #[test]
+        fn alchemy() {
+            let item_loader = item_loader();
+            let item = item_loader.load(1513).unwrap();
+
+            assert_eq!(item.cost, 320);
+            assert_eq!(item.high_alch(), 192);
+            assert_eq!(item.low_alch(), 128);
+        }
+
+        #[test]
+        fn bought() {
+            let item_loader = item_loader();
+            let item = item_loader.load(13191).unwrap();
+
+            assert_eq!(item.bought_link, Some(13190));
+            assert_eq!(item.bought_template, Some(13189));
+
+            let base = item_loader.ge_base_item(13191).unwrap();
+            assert_eq!(base.id, 13190);
+            assert_eq!(base.name, "Old school bond");
+            assert!(base.tradable);
+
+            assert_eq!(item_loader.ge_base_item(13190).unwrap().id, 13190);
+        }
+
+        #[test]
+        fn opcode_histogram() {
+            let histogram = ItemLoader::opcode_histogram(&test_util::osrs_cache()).unwrap();
+
+            assert_eq!(histogram[&2], 11163);
+            assert_eq!(histogram[&249], 1044);
+            assert!(!histogram.contains_key(&0));
+            assert!(!histogram.contains_key(&3));
+        }
+
+        #[test]
+        fn stacks() {
+            let item_loader = item_loader();
+            let item = item_loader.load(995).unwrap();
+
+            assert_eq!(item.name, "Coins");
+            assert_eq!(
+                item.stack_ids,
+                Some([996, 997, 998, 999, 1000, 1001, 1002, 1003, 1004, 0])
+            );
+            assert_eq!(
+                item.stack_count,
+                Some([2, 3, 4, 5, 25, 100, 250, 1000, 10000, 0])
+            );
+        }
+
         #[test]
         fn noted() {
             let item_loader = item_loader();
@@ -46,6 +242,109 @@ mod osrs {
             let item_loader = item_loader();
             assert!(item_loader.load(65_535).is_none());
         }
+
+        #[cfg(feature = "bincode")]
+        #[test]
+        fn bincode_round_trip() {
+            let item_loader = item_loader();
+            let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("items.bin");
+
+            item_loader.save_bincode(&path).unwrap();
+            let restored = ItemLoader::load_bincode(&path).unwrap();
+
+            assert_eq!(restored, item_loader);
+            assert_eq!(restored.load(1042).unwrap().name, "Blue partyhat");
+        }
+
+        #[cfg(feature = "bincode")]
+        #[test]
+        fn changes_since() {
+            let item_loader = item_loader();
+            let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("stale_items.bin");
+
+            // Make a stale snapshot: 1042 renamed, 1038 dropped and 65_000 added.
+            let mut stale = item_loader.clone();
+            stale.iter_mut().for_each(|(id, item)| {
+                if *id == 1042 {
+                    item.name = "Purple partyhat".to_owned();
+                }
+            });
+            let mut stale: std::collections::HashMap<_, _> = stale.into_iter().collect();
+            stale.remove(&1038);
+            stale.insert(65_000, Default::default());
+            std::fs::write(&path, bincode::serialize(&stale).unwrap()).unwrap();
+
+            let changes = item_loader.changes_since(&path).unwrap();
+
+            assert_eq!(changes.added, vec![1038]);
+            assert_eq!(changes.removed, vec![65_000]);
+            assert_eq!(changes.changed, vec![1042]);
+            assert!(item_loader.diff(&item_loader).is_empty());
+        }
+
+        #[test]
+        fn empty_archive() {
+            use std::{
+                fs::OpenOptions,
+                io::{Seek, SeekFrom, Write},
+            };
+
+            let path = test_util::osrs_cache_copy("empty_item_archive");
+
+            // Zero out the length of the archive containing all item definitions.
+            let mut idx = OpenOptions::new()
+                .write(true)
+                .open(path.join("main_file_cache.idx2"))
+                .unwrap();
+            idx.seek(SeekFrom::Start(10 * 6)).unwrap();
+            idx.write_all(&[0, 0, 0]).unwrap();
+
+            let cache = rscache::Cache::new(&path).unwrap();
+            let item_loader = ItemLoader::new(&cache).unwrap();
+
+            assert!(item_loader.load(1042).is_none());
+        }
+    }
+
+    mod decoding {
+        use super::test_util;
+        use rscache::loader::osrs::{ItemLoader, SequenceLoader, SpotAnimLoader};
+        use std::{collections::BTreeMap, fmt::Write};
+
+        #[test]
+        fn identical_to_buffered_decoders() {
+            let cache = test_util::osrs_cache();
+            let mut dump = String::new();
+
+            let item_loader = ItemLoader::new(&cache).unwrap();
+            let mut items: Vec<_> = item_loader.iter().map(|(_, item)| item.clone()).collect();
+            items.sort_by_key(|item| item.id);
+            for mut item in items {
+                // Params are a `HashMap`, sort them so the dump is the same on every run.
+                let params: BTreeMap<_, _> = std::mem::take(&mut item.params).into_iter().collect();
+                writeln!(dump, "{item:?} {params:?}").unwrap();
+            }
+
+            let sequence_loader = SequenceLoader::new(&cache).unwrap();
+            let mut sequences: Vec<_> = sequence_loader.iter().collect();
+            sequences.sort_by_key(|(id, _)| **id);
+            for (_, sequence) in sequences {
+                writeln!(dump, "{sequence:?}").unwrap();
+            }
+
+            let spot_anim_loader = SpotAnimLoader::new(&cache).unwrap();
+            let mut spot_anims: Vec<_> = spot_anim_loader.iter().collect();
+            spot_anims.sort_by_key(|(id, _)| **id);
+            for (_, spot_anim) in spot_anims {
+                writeln!(dump, "{spot_anim:?}").unwrap();
+            }
+
+            // Recorded when the decoders still read through a `BufReader`.
+            assert_eq!(
+                test_util::hash(dump.as_bytes()),
+                "a100b24392c9b4ba59b50f0a802ffe4956b80288"
+            );
+        }
     }
 
     mod npcs {
@@ -56,6 +355,18 @@ mod osrs {
             NpcLoader::new(&test_util::osrs_cache()).unwrap()
         }
 
+        #[test]
+        fn revision() {
+            use rscache::Cache;
+
+            // The fixture only decodes with the layout of its own revision.
+            let cache = Cache::new("./data/osrs_cache").unwrap();
+            assert!(NpcLoader::new(&cache).is_err());
+
+            let cache = cache.with_revision(test_util::OSRS_REVISION);
+            assert_eq!(NpcLoader::new(&cache).unwrap(), npc_loader());
+        }
+
         #[test]
         fn woodsman_tutor() {
             let npc_loader = npc_loader();
@@ -83,7 +394,7 @@ mod osrs {
 
     mod objects {
         use super::test_util;
-        use rscache::loader::osrs::ObjectLoader;
+        use rscache::{definition::osrs::ObjectType, loader::osrs::ObjectLoader};
 
         fn obj_loader() -> ObjectLoader {
             ObjectLoader::new(&test_util::osrs_cache()).unwrap()
@@ -95,7 +406,7 @@ mod osrs {
             let obj = obj_loader.load(25034).unwrap();
 
             assert_eq!(obj.name, "Law rift");
-            assert_eq!(obj.animation_id, 2178);
+            assert_eq!(obj.animation_id, Some(2178));
             assert!(obj.solid);
             assert!(!obj.obstruct_ground);
         }
@@ -132,6 +443,494 @@ mod osrs {
             assert!(obj.solid);
             assert!(!obj.obstruct_ground);
         }
+
+        #[test]
+        fn mud_pile() {
+            let obj_loader = obj_loader();
+            let obj = obj_loader.load(13).unwrap();
+
+            assert_eq!(obj.name, "Mud pile");
+            assert!(obj.contours_ground());
+            assert_eq!(obj.contoured_ground, Some(0));
+            assert!(!obj.delay_shading);
+            assert!(!obj.obstruct_ground);
+        }
+
+        #[test]
+        fn beehive() {
+            let obj_loader = obj_loader();
+            let obj = obj_loader.load(68).unwrap();
+
+            assert_eq!(obj.name, "Beehive");
+            assert_eq!(obj.ambient_sound_id, Some(3045));
+            assert_eq!(obj.ambient_sound_distance, 3);
+            assert!(obj.ambient_sound_ids.is_empty());
+        }
+
+        #[test]
+        fn random_ambient_sounds() {
+            let obj_loader = obj_loader();
+            let obj = obj_loader.load(16433).unwrap();
+
+            assert_eq!(obj.ambient_sound_id, Some(2184));
+            assert_eq!(obj.ambient_sound_distance, 15);
+            assert_eq!(obj.ambient_sound_ids, vec![1984, 1985, 1986, 1987, 1988]);
+            assert_eq!(obj.ambient_sound_change_ticks, (150, 300));
+        }
+
+        #[test]
+        fn ladder_map_scene() {
+            let obj_loader = obj_loader();
+            let obj = obj_loader.load(10).unwrap();
+
+            assert_eq!(obj.name, "Ladder");
+            assert_eq!(obj.map_scene_id, Some(7));
+            assert_eq!(obj.map_area_id, None);
+        }
+
+        #[test]
+        fn map_area() {
+            let obj_loader = obj_loader();
+            let obj = obj_loader.load(2734).unwrap();
+
+            assert_eq!(obj.map_scene_id, None);
+            assert_eq!(obj.map_area_id, Some(1));
+        }
+
+        #[test]
+        fn smoking_vent() {
+            let obj_loader = obj_loader();
+            let obj = obj_loader.load(5097).unwrap();
+
+            assert_eq!(obj.name, "Smoking vent");
+            assert_eq!(obj.object_type(), ObjectType::WallDecoration);
+            assert_eq!(obj.model_data.decor_displacement, 16);
+            assert_eq!(obj.model_data.offset_z, -60);
+        }
+
+        #[test]
+        fn displaced_wall_decoration() {
+            let obj_loader = obj_loader();
+            let obj = obj_loader.load(24187).unwrap();
+
+            assert_eq!(obj.object_type(), ObjectType::WallDecoration);
+            assert_eq!(obj.model_data.decor_displacement, 32);
+        }
+
+        #[test]
+        fn with_action() {
+            let obj_loader = obj_loader();
+
+            let mine = obj_loader.with_action("Mine");
+            assert_eq!(mine.len(), 138);
+            assert_eq!(mine[0].id, 2634);
+            assert_eq!(mine[0].name, "Rock slide");
+            assert_eq!(mine[1].id, 2704);
+            assert!(mine.windows(2).all(|pair| pair[0].id < pair[1].id));
+
+            assert_eq!(obj_loader.with_action("mine").len(), 138);
+            assert_eq!(obj_loader.with_action("Prospect").len(), 103);
+            assert!(obj_loader.with_action("Unknown action").is_empty());
+        }
+    }
+
+    mod areas {
+        use super::test_util;
+        use rscache::loader::osrs::AreaLoader;
+
+        #[test]
+        fn lumbridge() {
+            let area_loader = AreaLoader::new(&test_util::osrs_cache()).unwrap();
+            let area = area_loader.load(87).unwrap();
+
+            assert_eq!(area.name, "Lumbridge");
+            assert_eq!(area.text_color, 0xFFFFFF);
+            assert_eq!(area.category, 1129);
+        }
+    }
+
+    mod enums {
+        use super::test_util;
+        use rscache::{definition::osrs::MUSIC_TRACK_NAMES_ENUM, loader::osrs::EnumLoader};
+
+        #[test]
+        fn track_name() {
+            let enum_loader = EnumLoader::new(&test_util::osrs_cache()).unwrap();
+
+            assert_eq!(
+                enum_loader.track_name(MUSIC_TRACK_NAMES_ENUM, 6),
+                Some("Harmony")
+            );
+            assert_eq!(
+                enum_loader.track_name(MUSIC_TRACK_NAMES_ENUM, 1),
+                Some("Scape Original")
+            );
+            assert_eq!(enum_loader.track_name(MUSIC_TRACK_NAMES_ENUM, -1), None);
+            assert_eq!(enum_loader.track_name(u16::MAX, 6), None);
+        }
+    }
+
+    mod params {
+        use super::test_util;
+        use rscache::{
+            definition::osrs::ParamValue,
+            loader::osrs::{ItemLoader, ParamLoader},
+        };
+
+        #[test]
+        fn param_or_default() {
+            let cache = test_util::osrs_cache();
+            let item_loader = ItemLoader::new(&cache).unwrap();
+            let param_loader = ParamLoader::new(&cache).unwrap();
+
+            let item = item_loader.load(3643).unwrap();
+            assert_eq!(
+                item.param_or_default(186, &param_loader),
+                Some(ParamValue::Int(4180))
+            );
+
+            // Abyssal whip doesn't set param 186, its default is -1.
+            let item = item_loader.load(4151).unwrap();
+            assert!(!item.params.contains_key(&186));
+            assert_eq!(
+                item.param_or_default(186, &param_loader),
+                Some(ParamValue::Int(-1))
+            );
+
+            // Farming cape(t) only sets the first two of the string params.
+            let item = item_loader.load(9811).unwrap();
+            assert_eq!(
+                item.param_or_default(451, &param_loader),
+                Some(ParamValue::String("Boost".to_owned()))
+            );
+            assert_eq!(
+                item.param_or_default(453, &param_loader),
+                Some(ParamValue::String(String::new()))
+            );
+
+            assert_eq!(item.param_or_default(u32::MAX, &param_loader), None);
+        }
+    }
+
+    mod spot_anims {
+        use super::test_util;
+        use rscache::loader::osrs::{SequenceLoader, SpotAnimLoader};
+
+        #[test]
+        fn animation() {
+            let cache = test_util::osrs_cache();
+            let spot_anim_loader = SpotAnimLoader::new(&cache).unwrap();
+            let sequence_loader = SequenceLoader::new(&cache).unwrap();
+
+            let spot_anim = spot_anim_loader.load(100).unwrap();
+            let animation = spot_anim.animation(&sequence_loader).unwrap();
+
+            assert_eq!(animation.id, 659);
+            assert_eq!(animation.frame_lengths, vec![2, 2, 2, 2]);
+            assert_eq!(animation.frame_ids[0] >> 16, 1011);
+        }
+    }
+
+    mod frames {
+        use super::test_util;
+        use rscache::loader::osrs::{FrameLoader, SequenceLoader};
+
+        #[test]
+        fn load_frame() {
+            let cache = test_util::osrs_cache();
+            let mut frame_loader = FrameLoader::new(&cache);
+
+            let frame = frame_loader.load(1011 << 16).unwrap().unwrap();
+            assert_eq!(frame.skeleton_id, 902);
+            assert_eq!(frame.transforms.len(), 20);
+
+            // Slot 11 scales, the archive is split in chunks so this is read from the last one.
+            let scale = frame.transforms[9];
+            assert_eq!(scale.slot, 11);
+            assert_eq!((scale.x, scale.y, scale.z), (180, 180, 180));
+
+            assert_eq!(frame_loader.skeleton(902).unwrap().len(), 27);
+            assert!(frame_loader.load(1011 << 16 | 4).unwrap().is_none());
+        }
+
+        #[test]
+        fn sequence_frames() {
+            let cache = test_util::osrs_cache();
+            let sequence_loader = SequenceLoader::new(&cache).unwrap();
+            let mut frame_loader = FrameLoader::new(&cache);
+
+            for &frame_id in &sequence_loader.load(659).unwrap().frame_ids {
+                assert!(frame_loader.load(frame_id).unwrap().is_some());
+            }
+        }
+    }
+
+    mod hitsplats {
+        use super::test_util;
+        use rscache::loader::osrs::HitsplatLoader;
+
+        #[test]
+        fn load_hitsplats() {
+            let hitsplat_loader = HitsplatLoader::new(&test_util::osrs_cache()).unwrap();
+            let hitsplat = hitsplat_loader.load(0).unwrap();
+
+            assert_eq!(hitsplat_loader.iter().count(), 14);
+            assert_eq!(hitsplat.format, "%1");
+            assert_eq!(hitsplat.middle_sprite_id, Some(1358));
+        }
+    }
+
+    mod identikits {
+        use super::test_util;
+        use rscache::loader::osrs::IdentikitLoader;
+
+        #[test]
+        fn male_defaults() {
+            let kit_loader = IdentikitLoader::new(&test_util::osrs_cache()).unwrap();
+            let defaults = kit_loader.defaults(false).map(Option::unwrap);
+
+            assert_eq!(defaults, [0, 10, 18, 26, 33, 36, 42]);
+        }
+
+        #[test]
+        fn female_defaults() {
+            let kit_loader = IdentikitLoader::new(&test_util::osrs_cache()).unwrap();
+            let defaults = kit_loader.defaults(true);
+
+            assert_eq!(defaults[1], None);
+            assert_eq!(
+                defaults.map(Option::unwrap_or_default),
+                [45, 0, 56, 61, 67, 70, 79]
+            );
+            assert!(kit_loader.load(45).unwrap().female());
+        }
+    }
+
+    mod overlays {
+        use super::test_util;
+        use rscache::loader::osrs::{OverlayLoader, TextureLoader};
+
+        #[test]
+        fn textured_overlay() {
+            let cache = test_util::osrs_cache();
+            let overlay_loader = OverlayLoader::new(&cache).unwrap();
+            let texture_loader = TextureLoader::new(&cache).unwrap();
+
+            let overlay = overlay_loader.load(4).unwrap();
+            let texture = overlay.texture(&texture_loader).unwrap();
+
+            assert_eq!(overlay.texture_id, Some(3));
+            assert_eq!(texture.id, 3);
+            assert_eq!(texture.sprite_ids, vec![450]);
+        }
+
+        #[test]
+        fn untextured_overlay() {
+            let cache = test_util::osrs_cache();
+            let overlay_loader = OverlayLoader::new(&cache).unwrap();
+            let texture_loader = TextureLoader::new(&cache).unwrap();
+
+            let overlay = overlay_loader.load(1).unwrap();
+
+            assert_eq!(overlay.color, 0x444444);
+            assert!(overlay.texture(&texture_loader).is_none());
+        }
+    }
+
+    mod textures {
+        use super::test_util;
+        use rscache::loader::osrs::{SpriteLoader, TextureLoader};
+
+        #[test]
+        fn render() {
+            let cache = test_util::osrs_cache();
+            let texture_loader = TextureLoader::new(&cache).unwrap();
+            let sprite_loader = SpriteLoader::new(&cache).unwrap();
+
+            let texture = texture_loader.load(3).unwrap();
+            for size in [64, 128] {
+                let pixels = texture.render(&sprite_loader, size).unwrap();
+                assert_eq!(pixels.len(), (size * size * 4) as usize);
+            }
+        }
+
+        #[test]
+        fn render_missing_sprite() {
+            let cache = test_util::osrs_cache();
+            let texture_loader = TextureLoader::new(&cache).unwrap();
+
+            let texture = texture_loader.load(3).unwrap();
+            assert!(texture.render(&SpriteLoader::default(), 64).is_err());
+        }
+    }
+
+    mod models {
+        use super::test_util;
+        use rscache::loader::osrs::ModelLoader;
+
+        #[test]
+        fn old_format() {
+            let cache = test_util::osrs_cache();
+            let mut model_loader = ModelLoader::new(&cache);
+            let model = model_loader.load(2635).unwrap();
+
+            assert_eq!(model.vertex_count(), 42);
+            assert_eq!(model.face_count(), 52);
+            assert_eq!(model.face_colors.len(), 52);
+            assert_eq!(model.face_colors[0], 926);
+            assert_eq!(model.vertices[0], (0, -7, -9));
+            assert_eq!(model.faces[0], [0, 1, 2]);
+        }
+
+        #[test]
+        fn new_format() {
+            let cache = test_util::osrs_cache();
+            let mut model_loader = ModelLoader::new(&cache);
+            let model = model_loader.load(305).unwrap();
+
+            assert_eq!(model.vertex_count(), 57);
+            assert_eq!(model.face_count(), 94);
+            assert_eq!(model.vertices[0], (11, -123, 5));
+            assert_eq!(model.faces[93], [50, 39, 38]);
+            assert!(model
+                .faces
+                .iter()
+                .flatten()
+                .all(|&vertex| (vertex as usize) < model.vertex_count()));
+        }
+
+        #[test]
+        fn apply_recolor() {
+            let cache = test_util::osrs_cache();
+            let mut model_loader = ModelLoader::new(&cache);
+            let original = model_loader.load(305).unwrap().clone();
+            let find = original.face_colors[0];
+
+            let mut model = original.clone();
+            model.apply_recolor(&[find, 1], &[1000, 2]);
+
+            let mut untouched = 0;
+            for (before, after) in original.face_colors.iter().zip(&model.face_colors) {
+                if *before == find {
+                    assert_eq!(*after, 1000);
+                } else {
+                    assert_eq!(after, before);
+                    untouched += 1;
+                }
+            }
+            assert!(untouched > 0);
+        }
+
+        #[cfg(feature = "obj")]
+        #[test]
+        fn to_obj() {
+            let cache = test_util::osrs_cache();
+            let mut model_loader = ModelLoader::new(&cache);
+            let model = model_loader.load(2635).unwrap();
+
+            let obj = model.to_obj();
+            let vertices = obj.lines().filter(|line| line.starts_with("v ")).count();
+            let faces = obj.lines().filter(|line| line.starts_with("f ")).count();
+            assert_eq!(vertices, model.vertex_count());
+            assert_eq!(faces, model.face_count());
+            assert!(obj.starts_with("mtllib model_2635.mtl"));
+
+            let mtl = model.to_mtl();
+            assert!(mtl.contains("newmtl hsl_926\n"));
+        }
+    }
+
+    mod scripts {
+        use super::test_util;
+        use rscache::loader::osrs::ScriptLoader;
+
+        #[test]
+        fn load_script() {
+            let cache = test_util::osrs_cache();
+            let mut script_loader = ScriptLoader::new(&cache);
+
+            assert_eq!(script_loader.load(0).unwrap().len(), 940);
+            assert_eq!(script_loader.load(1).unwrap().len(), 146);
+            assert!(script_loader.load(60_000).is_err());
+        }
+    }
+
+    mod sprites {
+        use super::test_util;
+        use rscache::loader::osrs::SpriteLoader;
+
+        #[test]
+        fn animated_sprite() {
+            let sprite_loader = SpriteLoader::new(&test_util::osrs_cache()).unwrap();
+            let sprite = sprite_loader.load(299).unwrap();
+
+            assert_eq!(sprite.frame_count(), 8);
+            assert_eq!((sprite.max_width, sprite.max_height), (16, 16));
+
+            let frame = &sprite.frames[0];
+            assert_eq!((frame.width, frame.height), (10, 10));
+            assert_eq!((frame.offset_x, sprite.frames[1].offset_x), (3, 1));
+            assert_eq!(frame.pixels.len(), 100);
+        }
+
+        #[test]
+        fn single_frame_sprite() {
+            let sprite_loader = SpriteLoader::new(&test_util::osrs_cache()).unwrap();
+            let sprite = sprite_loader.load(0).unwrap();
+
+            assert_eq!(sprite.frame_count(), 1);
+            assert_eq!((sprite.max_width, sprite.max_height), (40, 40));
+        }
+    }
+
+    mod varbits {
+        use super::test_util;
+        use rscache::{loader::osrs::VarbitLoader, util};
+
+        #[test]
+        fn varbit_resolution() {
+            let varbit_loader = VarbitLoader::new(&test_util::osrs_cache()).unwrap();
+            let varbit = varbit_loader.load(1).unwrap();
+
+            assert_eq!(varbit.varp_index, 318);
+            assert_eq!(varbit.least_significant_bit, 1);
+            assert_eq!(varbit.most_significant_bit, 1);
+
+            assert_eq!(util::resolve_config(Some(varbit), 0b010), 1);
+            assert_eq!(util::resolve_config(Some(varbit), 0b101), 0);
+        }
+
+        #[test]
+        fn varp_resolution() {
+            assert_eq!(util::resolve_config(None, 0b101), 0b101);
+            assert_eq!(util::resolve_config(None, -1), -1);
+        }
+    }
+
+    mod world_maps {
+        use super::test_util;
+        use rscache::{definition::osrs::WorldMapBounds, loader::osrs::WorldMapLoader};
+
+        #[test]
+        fn surface() {
+            let world_map_loader = WorldMapLoader::new(&test_util::osrs_cache()).unwrap();
+            let world_map = world_map_loader.load(0).unwrap();
+
+            assert_eq!(world_map.internal_name, "main");
+            assert_eq!(world_map.name, "RuneScape Surface");
+            assert_eq!(world_map.origin, Some((3232, 3232, 0)));
+            assert_eq!(world_map.zoom, 75);
+            assert_eq!(world_map.sections.len(), 3);
+            assert_eq!(
+                world_map.sections[0].old,
+                WorldMapBounds {
+                    region_x: (18, 21),
+                    region_y: (54, 63),
+                    chunk_x: (0, 7),
+                    chunk_y: (0, 7),
+                }
+            );
+        }
     }
 
     mod locations {
@@ -179,7 +978,7 @@ mod rs3 {
     mod items {
         use super::test_util;
         use rscache::loader::rs3::ItemLoader;
-        
+
         fn item_loader() -> ItemLoader {
             ItemLoader::new(&test_util::rs3_cache()).unwrap()
         }
@@ -68,6 +68,665 @@ mod osrs {
         let cache = test_util::osrs_cache();
         assert!(cache.read(2, 25_000).is_err());
     }
+
+    #[test]
+    fn archive_exists() {
+        let cache = test_util::osrs_cache();
+
+        assert!(cache.archive_exists(2, 10));
+        assert!(cache.archive_exists(0, 191));
+        assert!(!cache.archive_exists(2, 65535));
+        assert!(!cache.archive_exists(200, 0));
+    }
+
+    #[test]
+    fn update_responses() {
+        let cache = test_util::osrs_cache();
+        let responses = cache.read_update_responses(&[(255, 10), (0, 191)]).unwrap();
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].len(), 80);
+        assert_eq!(responses[0], cache.read_update_response(255, 10).unwrap());
+        assert_eq!(&responses[0][..3], &[255, 0, 10]);
+
+        // The container of 2055 bytes loses its version and gains the index and
+        // archive ids, followed by a separator every 512 bytes.
+        let response = &responses[1];
+        let container = cache.read(0, 191).unwrap();
+        assert_eq!(&response[..3], &[0, 0, 191]);
+        assert_eq!(&response[3..8], &container[..5]);
+        assert_eq!(response.len(), 2055 - 2 + 3 + 4);
+        for separator in (512..response.len()).step_by(512) {
+            assert_eq!(response[separator], 0xFF);
+        }
+    }
+
+    #[test]
+    fn update_response_wide_archive_id() {
+        use rscache::{error::ReadError, Error};
+
+        let cache = test_util::osrs_cache();
+
+        assert!(matches!(
+            cache.read_update_response(2, 65_536),
+            Err(Error::Read(ReadError::ArchiveIdOutOfRange { id: 65_536 }))
+        ));
+        assert!(matches!(
+            cache.read_update_responses(&[(0, 191), (2, 70_000)]),
+            Err(Error::Read(ReadError::ArchiveIdOutOfRange { id: 70_000 }))
+        ));
+    }
+
+    #[test]
+    fn index_handle() {
+        let cache = test_util::osrs_cache();
+        let index = cache.index(0).unwrap();
+
+        assert_eq!(index.id(), 0);
+        assert!(index.exists(191));
+        assert!(!index.exists(65535));
+
+        let buffer = index.read(191).unwrap();
+        let hash = test_util::hash(&buffer);
+        assert_eq!(&hash, "cd459f6ccfbd81c1e3bfadf899624f2519e207a9");
+
+        let archive_ids: Vec<u32> = index.archive_ids().collect();
+        assert!(archive_ids.windows(2).all(|ids| ids[0] < ids[1]));
+        for archive_id in archive_ids.iter().take(10) {
+            assert!(index.exists(*archive_id));
+            assert_eq!(
+                index.read(*archive_id).unwrap().as_slice(),
+                cache.read(0, *archive_id).unwrap().as_slice()
+            );
+        }
+
+        assert!(cache.index(200).is_err());
+    }
+
+    #[test]
+    fn decompressed_len() {
+        let cache = test_util::osrs_cache();
+
+        for (index_id, archive_id) in [(255, 10), (0, 191), (2, 10), (7, 24918)] {
+            let len = cache
+                .archive_decompressed_len(index_id, archive_id)
+                .unwrap();
+            let buffer = cache.read(index_id, archive_id).unwrap().decode().unwrap();
+
+            assert_eq!(len, buffer.len());
+        }
+    }
+
+    #[test]
+    fn overlay_shadows_base() {
+        use rscache::Cache;
+        use std::{
+            fs::OpenOptions,
+            io::{Seek, SeekFrom, Write},
+        };
+
+        let base = test_util::osrs_cache_copy("overlay_base");
+
+        // Zero out the length of the item archive in the base, the overlay still has it.
+        let mut idx = OpenOptions::new()
+            .write(true)
+            .open(base.join("main_file_cache.idx2"))
+            .unwrap();
+        idx.seek(SeekFrom::Start(10 * 6)).unwrap();
+        idx.write_all(&[0, 0, 0]).unwrap();
+
+        assert!(Cache::new(&base).unwrap().read(2, 10).unwrap().is_empty());
+
+        let cache = Cache::with_overlay(&base, "./data/osrs_cache").unwrap();
+        let buffer = cache.read(2, 10).unwrap();
+
+        let hash = test_util::hash(&buffer);
+        assert_eq!(&hash, "c6ee1518e9a39a42ecaf946c6c84a942cb3102f4");
+        assert_eq!(buffer.len(), 260_537);
+    }
+
+    #[test]
+    fn sparse_overlay() {
+        use rscache::{
+            codec::{self, Compression},
+            Cache,
+        };
+
+        let base = test_util::osrs_cache();
+        let huffman_id = base.resolve_names(10, ["huffman"]).unwrap()["huffman"];
+        let huffman = base.huffman_table().unwrap().finalize();
+        // Stored uncompressed the huffman archive is longer than the one in the base.
+        let huffman_archive = codec::encode(Compression::None, &huffman, None).unwrap();
+        let params = base.read(2, 11).unwrap().finalize();
+
+        let overlay = test_util::write_sparse_cache(
+            "sparse_overlay",
+            &[(2, 10, &params), (10, huffman_id, &huffman_archive)],
+        );
+        let cache = Cache::with_overlay("./data/osrs_cache", overlay).unwrap();
+
+        // Shadowed by the overlay.
+        assert_eq!(cache.read(2, 10).unwrap().finalize(), params);
+        assert_eq!(cache.huffman_table().unwrap().finalize(), huffman);
+
+        // Empty in the overlay and missing from the overlay.
+        for (index_id, archive_id) in [(2, 6), (255, 2), (2, 11), (0, 191)] {
+            assert_eq!(
+                cache.read(index_id, archive_id).unwrap().finalize(),
+                base.read(index_id, archive_id).unwrap().finalize()
+            );
+        }
+    }
+
+    #[test]
+    fn builder() {
+        use rscache::Cache;
+
+        let cache = Cache::builder()
+            .cache_capacity(4)
+            .open("./data/osrs_cache")
+            .unwrap();
+
+        cache.read_decoded(2, 10).unwrap();
+        cache.read_decoded(2, 10).unwrap();
+
+        let stats = cache.archive_cache_stats().unwrap();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+
+        let cache = Cache::builder().open("./data/osrs_cache").unwrap();
+        assert!(cache.archive_cache_stats().is_none());
+    }
+
+    #[test]
+    fn builder_mmap() {
+        use rscache::Cache;
+
+        let mapped = Cache::builder()
+            .mmap(true)
+            .open("./data/osrs_cache")
+            .unwrap();
+        let loaded = Cache::builder()
+            .mmap(false)
+            .open("./data/osrs_cache")
+            .unwrap();
+
+        for (index_id, archive_id) in [(255, 2), (2, 10), (0, 191), (7, 24918)] {
+            assert_eq!(
+                mapped.read(index_id, archive_id).unwrap().finalize(),
+                loaded.read(index_id, archive_id).unwrap().finalize()
+            );
+        }
+    }
+
+    #[test]
+    fn builder_unknown_opcode_policy() {
+        use rscache::{
+            codec::{self, Compression},
+            definition::osrs::{FetchDefinition, ItemDefinition, RawDefinition},
+            loader::UnknownOpcodePolicy,
+            Cache,
+        };
+        use std::collections::HashMap;
+
+        let base = test_util::osrs_cache();
+        let raw_items: HashMap<u16, RawDefinition<ItemDefinition>> =
+            ItemDefinition::fetch_from_archive(&base, 2, 10).unwrap();
+        let mut files: Vec<Vec<u8>> = (0..23458).map(|id| raw_items[&id].raw().to_vec()).collect();
+
+        // Opcode 200 (unknown) after the name of item 1.
+        files[1] = vec![2, b'H', b'a', b't', 0, 200, 0];
+
+        let mut group = files.concat();
+        let mut previous_len = 0;
+        for file in &files {
+            group.extend_from_slice(&(file.len() as i32 - previous_len).to_be_bytes());
+            previous_len = file.len() as i32;
+        }
+        group.push(1);
+
+        let archive = codec::encode(Compression::None, &group, None).unwrap();
+        let overlay = test_util::write_sparse_cache("builder_policy", &[(2, 10, &archive)]);
+
+        let cache = Cache::builder()
+            .overlay(&overlay)
+            .open("./data/osrs_cache")
+            .unwrap();
+        assert!(ItemDefinition::fetch_from_archive::<ItemDefinition>(&cache, 2, 10).is_err());
+
+        let cache = Cache::builder()
+            .overlay(&overlay)
+            .mmap(false)
+            .unknown_opcode_policy(UnknownOpcodePolicy::Warn)
+            .open("./data/osrs_cache")
+            .unwrap();
+        let items: HashMap<u16, ItemDefinition> =
+            ItemDefinition::fetch_from_archive(&cache, 2, 10).unwrap();
+        assert_eq!(items[&1].name, "Hat");
+        assert_eq!(items[&4151].name, "Abyssal whip");
+    }
+
+    #[test]
+    fn prefetch() {
+        let cache = test_util::osrs_cache().with_archive_cache(8);
+        let coords = [(255, 10), (0, 191), (2, 10)];
+
+        cache.prefetch(&coords).unwrap();
+
+        let stats = cache.archive_cache_stats().unwrap();
+        assert_eq!(stats.misses, 3);
+        assert_eq!(stats.hits, 0);
+
+        for (index_id, archive_id) in coords {
+            let buffer = cache.read_decoded(index_id, archive_id).unwrap();
+            let expected = cache.read(index_id, archive_id).unwrap().decode().unwrap();
+
+            assert_eq!(buffer.as_slice(), expected.as_slice());
+        }
+
+        let stats = cache.archive_cache_stats().unwrap();
+        assert_eq!(stats.misses, 3);
+        assert_eq!(stats.hits, 3);
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let cache = test_util::osrs_cache();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let cache = cache.clone();
+                std::thread::spawn(move || cache.read(0, 191).unwrap())
+            })
+            .collect();
+
+        for handle in handles {
+            let buffer = handle.join().unwrap();
+
+            let hash = test_util::hash(&buffer);
+            assert_eq!(&hash, "cd459f6ccfbd81c1e3bfadf899624f2519e207a9");
+            assert_eq!(buffer.len(), 2055);
+        }
+    }
+
+    #[test]
+    fn custom_decompressor() {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        use rscache::codec::{Compression, Decompressor, DefaultDecompressor};
+
+        #[derive(Debug, Default)]
+        struct Counting(Arc<AtomicUsize>);
+
+        impl Decompressor for Counting {
+            fn decompress(
+                &self,
+                compression: Compression,
+                data: &[u8],
+                decompressed_len: usize,
+            ) -> Result<Vec<u8>, rscache::Error> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                match compression {
+                    Compression::None => Ok(data.to_vec()),
+                    _ => DefaultDecompressor.decompress(compression, data, decompressed_len),
+                }
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cache = test_util::osrs_cache();
+        let custom = cache
+            .clone()
+            .with_decompressor(Counting(Arc::clone(&calls)));
+
+        for (index_id, archive_id) in [(2, 31), (0, 191)] {
+            let expected = cache.read(index_id, archive_id).unwrap().decode().unwrap();
+            let buffer = custom.read_decoded(index_id, archive_id).unwrap();
+            assert_eq!(buffer.as_slice(), expected.as_slice());
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn archive_id() {
+        use rscache::{error::ReadError, index::ArchiveId};
+
+        let wide = ArchiveId::from(65_536_u32);
+        let narrow = ArchiveId::from(1042_u16);
+
+        assert_eq!(u32::from(wide), 65_536);
+        assert!(wide.uses_expanded_header());
+        assert_eq!(
+            u16::try_from(wide),
+            Err(ReadError::ArchiveIdOutOfRange { id: 65_536 })
+        );
+        assert_eq!(u16::try_from(narrow), Ok(1042));
+        assert!(!narrow.uses_expanded_header());
+    }
+
+    #[test]
+    fn stats() {
+        let cache = test_util::osrs_cache();
+        let stats = cache.stats().unwrap();
+
+        assert_eq!(stats.index_count, 21);
+        assert_eq!(stats.archive_count, 58634);
+        assert!(stats.encrypted_count > 0 && stats.encrypted_count < stats.archive_count);
+        assert!(stats.total_compressed > 0);
+        assert!(stats.total_decompressed > stats.total_compressed);
+    }
+
+    #[test]
+    fn read_decoded_with() {
+        use rscache::definition::osrs::{Definition, ItemDefinition};
+        use runefs::{ArchiveFileGroup, IndexMetadata};
+
+        let cache = test_util::osrs_cache();
+        let metadata =
+            IndexMetadata::from_buffer(cache.read(255, 2).unwrap().decode().unwrap()).unwrap();
+        let entry_count = metadata
+            .iter()
+            .find(|archive| archive.id == 10)
+            .unwrap()
+            .entry_count;
+
+        let whip = cache
+            .read_decoded_with(2, 10, |buffer| {
+                let files = ArchiveFileGroup::from_buffer(buffer, entry_count);
+                let file = files.iter().find(|file| file.id == 4151).unwrap();
+
+                ItemDefinition::new(4151, &file.data)
+            })
+            .unwrap();
+
+        assert_eq!(whip.name, "Abyssal whip");
+        assert!(cache
+            .read_decoded_with(2, 10, |_| -> Result<(), rscache::Error> {
+                Err(std::io::Error::other("stop").into())
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn sectors() {
+        let cache = test_util::osrs_cache();
+        let sectors = cache
+            .sectors(2, 10)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let data: Vec<u8> = sectors
+            .iter()
+            .flat_map(|sector| sector.data_block.iter().copied())
+            .collect();
+        assert_eq!(data, cache.read(2, 10).unwrap().as_slice());
+
+        // The data blocks point into the map of the data file, so the distance between two
+        // blocks is the distance between their sectors in the file.
+        for window in sectors.windows(3) {
+            let (from, to) = (window[0].header.next, window[1].header.next);
+            let distance =
+                window[2].data_block.as_ptr() as isize - window[1].data_block.as_ptr() as isize;
+
+            assert_eq!(distance, (to as isize - from as isize) * 520);
+        }
+        assert!(cache.sectors(2, 100).is_err());
+    }
+
+    #[test]
+    fn sectors_follow_read() {
+        let cache = test_util::osrs_cache();
+
+        for (index_id, archive_id) in [(255, 2), (2, 10), (0, 191), (7, 24918)] {
+            let buffer = cache.read(index_id, archive_id).unwrap();
+            let sectors = cache
+                .sectors(index_id, archive_id)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            assert!(sectors.len() > 1);
+            assert_eq!(sectors.len(), buffer.len().div_ceil(512));
+            let data: Vec<u8> = sectors
+                .iter()
+                .flat_map(|sector| sector.data_block.iter().copied())
+                .collect();
+            assert_eq!(data, buffer.as_slice());
+        }
+    }
+
+    #[test]
+    fn archive_count() {
+        let cache = test_util::osrs_cache();
+
+        assert_eq!(cache.archive_count(2).unwrap(), 32);
+        assert_eq!(cache.archive_count(7).unwrap(), 37273);
+        assert_eq!(
+            cache.archive_count(7).unwrap(),
+            cache.index(7).unwrap().archive_ids().count()
+        );
+        assert!(cache.archive_count(100).is_err());
+        assert_eq!(cache.total_archives(), 58634);
+    }
+
+    #[test]
+    fn read_archive_struct() {
+        let cache = test_util::osrs_cache();
+        let models = cache.index(7).unwrap();
+        let archive_id = models.archive_ids().max().unwrap();
+        let archive = models.archive_ref(archive_id).unwrap();
+
+        assert_eq!(archive_id, 37272);
+        assert_eq!(
+            cache.read_archive_struct(archive).unwrap(),
+            cache.read(7, archive_id).unwrap().as_slice()
+        );
+        assert!(models.archive_ref(65536).is_none());
+    }
+
+    #[test]
+    fn read_archive_raw() {
+        use rscache::codec;
+
+        let cache = test_util::osrs_cache();
+
+        for (index_id, archive_id) in [(2, 10), (2, 31), (7, 305)] {
+            let raw = cache.read_archive_raw(index_id, archive_id).unwrap();
+            let (data, _) = codec::decode(&raw).unwrap();
+            let expected = cache.read_decoded(index_id, archive_id).unwrap();
+
+            assert_eq!(
+                raw.as_slice(),
+                cache.read(index_id, archive_id).unwrap().as_slice()
+            );
+            assert_eq!(data, expected.as_slice());
+        }
+    }
+
+    #[test]
+    fn misaligned_index_file() {
+        use rscache::{error::ReadError, Cache, Error};
+        use std::{fs::OpenOptions, io::Write};
+
+        let path = test_util::osrs_cache_copy("misaligned_index");
+
+        // idx10 holds 3 entries, a stray byte leaves a partial fourth entry.
+        let mut idx = OpenOptions::new()
+            .append(true)
+            .open(path.join("main_file_cache.idx10"))
+            .unwrap();
+        idx.write_all(&[0]).unwrap();
+
+        match Cache::new(&path) {
+            Err(Error::Read(ReadError::InvalidIndexFile { index_id, len })) => {
+                assert_eq!(index_id, 10);
+                assert_eq!(len, 19);
+            }
+            other => panic!("expected an invalid index file error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn corrupted_middle_sector() {
+        use rscache::{Cache, Error};
+        use runefs::error::{Error as RuneFsError, ReadError};
+        use std::{
+            fs::{self, OpenOptions},
+            io::{Seek, SeekFrom, Write},
+        };
+
+        let path = test_util::osrs_cache_copy("corrupted_sector");
+        let data_path = path.join("main_file_cache.dat2");
+        // The data file is linked to the original, make a real copy before writing to it.
+        fs::remove_file(&data_path).unwrap();
+        fs::copy("./data/osrs_cache/main_file_cache.dat2", &data_path).unwrap();
+
+        let cache = Cache::new(&path).unwrap();
+        let sectors = cache
+            .sectors(2, 10)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let middle = sectors.len() / 2;
+        let offset = sectors[middle - 1].header.next as u64 * 520;
+        drop(cache);
+
+        // Archive id, chunk and index id of the header, each written in turn.
+        let corruptions: [(u64, &[u8]); 3] = [(0, &[0, 11]), (2, &[0xFF, 0xFF]), (7, &[3])];
+        let mut data = OpenOptions::new().write(true).open(&data_path).unwrap();
+        for (field, bytes) in corruptions {
+            let original =
+                fs::read(&data_path).unwrap()[(offset + field) as usize..][..bytes.len()].to_vec();
+            data.seek(SeekFrom::Start(offset + field)).unwrap();
+            data.write_all(bytes).unwrap();
+
+            let cache = Cache::new(&path).unwrap();
+            let error = cache.read(2, 10).unwrap_err();
+            match (field, error) {
+                (0, Error::RuneFs(RuneFsError::Read(ReadError::SectorArchiveMismatch(11, 10)))) => {
+                }
+                (
+                    2,
+                    Error::RuneFs(RuneFsError::Read(ReadError::SectorChunkMismatch(0xFFFF, chunk))),
+                ) => {
+                    assert_eq!(chunk, middle);
+                }
+                (7, Error::RuneFs(RuneFsError::Read(ReadError::SectorIndexMismatch(3, 2)))) => {}
+                (field, error) => panic!("unexpected error for field {field}: {error:?}"),
+            }
+
+            // The sectors before the corrupted one are still valid.
+            let results: Vec<_> = cache.sectors(2, 10).unwrap().collect();
+            assert_eq!(results.len(), middle + 1);
+            assert!(results[..middle].iter().all(Result::is_ok));
+            assert!(results[middle].is_err());
+
+            data.seek(SeekFrom::Start(offset + field)).unwrap();
+            data.write_all(&original).unwrap();
+        }
+
+        assert!(Cache::new(&path).unwrap().read(2, 10).is_ok());
+    }
+
+    #[test]
+    fn name_index() {
+        use rscache::util::djd2;
+
+        let cache = test_util::osrs_cache();
+
+        let name_index = cache.name_index(10).unwrap();
+        assert_eq!(name_index.len(), 3);
+        assert_eq!(name_index[&djd2::hash("huffman")], 1);
+
+        let names = cache
+            .resolve_names(5, ["m50_50", "l50_50", "not_a_map"])
+            .unwrap();
+        assert_eq!(names["m50_50"], 382);
+        assert!(names.contains_key("l50_50"));
+        assert!(!names.contains_key("not_a_map"));
+
+        assert!(cache.name_index(2).unwrap().is_empty());
+        assert!(cache.name_index(200).is_err());
+    }
+
+    #[test]
+    fn reference_table() {
+        let cache = test_util::osrs_cache();
+
+        let table = cache.index(2).unwrap().reference_table().unwrap();
+        assert_eq!(table.protocol, 6);
+        assert_eq!(table.version, Some(2097));
+        assert!(table.lengths.is_empty());
+        assert_eq!(table.crcs.len(), table.archive_ids.len());
+
+        let table = cache.index(0).unwrap().reference_table().unwrap();
+        assert_eq!(table.protocol, 5);
+        assert_eq!(table.version, None);
+    }
+
+    #[test]
+    fn reference_table_protocol_6() {
+        use rscache::index::{ArchiveLengths, ReferenceTable};
+
+        // Protocol and version, flags (named and sized), archive count and id deltas.
+        let header = [6, 0, 0, 0, 42, 1 | 4, 0, 2, 0, 1, 0, 3];
+        let name_hashes_and_crcs = [0, 0, 0, 7, 0, 0, 0, 8, 0, 0, 0, 1, 0, 0, 0, 2];
+        let lengths = [0, 0, 0, 10, 0, 0, 0, 20, 0, 0, 0, 30, 0, 0, 0, 40];
+        let buffer = [&header[..], &name_hashes_and_crcs, &lengths].concat();
+
+        let table = ReferenceTable::from_buffer(&buffer).unwrap();
+
+        assert_eq!(table.version, Some(42));
+        assert_eq!(table.archive_ids, vec![1, 4]);
+        assert_eq!(table.name_hashes, Some(vec![7, 8]));
+        assert_eq!(table.crcs, vec![1, 2]);
+        assert_eq!(table.uncompressed_crcs, None);
+        assert_eq!(table.whirlpool, None);
+        assert_eq!(
+            table.lengths,
+            vec![
+                ArchiveLengths {
+                    archive_id: 1,
+                    compressed: 10,
+                    decompressed: 20
+                },
+                ArchiveLengths {
+                    archive_id: 4,
+                    compressed: 30,
+                    decompressed: 40
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reference_table_protocol_7() {
+        use rscache::{error::ReadError, index::ReferenceTable, Error};
+
+        // Protocol and version, flags (whirlpool and sized), archive count as a smart and
+        // id deltas as smarts, the second one is a big smart.
+        let header = [7, 0, 0, 0, 1, 2 | 4, 0, 2, 0, 5, 0x80, 1, 0, 0];
+        let crcs_and_whirlpools = [&[0; 8][..], &[1; 64], &[2; 64]].concat();
+        let lengths = [0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4];
+        let buffer = [&header[..], &crcs_and_whirlpools, &lengths].concat();
+
+        let table = ReferenceTable::from_buffer(&buffer).unwrap();
+        assert_eq!(table.whirlpool, Some(vec![[1; 64], [2; 64]]));
+        assert_eq!(table.lengths[0].archive_id, 5);
+        assert_eq!(table.lengths[1].archive_id, 65_541);
+        assert_eq!(table.lengths[1].compressed, 3);
+        assert_eq!(table.lengths[1].decompressed, 4);
+
+        assert!(matches!(
+            ReferenceTable::from_buffer(&[8]),
+            Err(Error::Read(ReadError::UnsupportedProtocol { protocol: 8 }))
+        ));
+    }
 }
 
 #[cfg(all(test, feature = "rs3"))]
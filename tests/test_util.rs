@@ -1,8 +1,21 @@
 use rscache::Cache;
 use sha1_smol::Sha1;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The revision the fixture cache is decoded as.
+///
+/// The fixture predates revision 210, every older revision stores npcs and objects the same.
+pub const OSRS_REVISION: u16 = 209;
 
 pub fn osrs_cache() -> Cache {
-    Cache::new("./data/osrs_cache").unwrap()
+    Cache::builder()
+        .revision(OSRS_REVISION)
+        .open("./data/osrs_cache")
+        .unwrap()
 }
 
 #[cfg(all(test, feature = "rs3"))]
@@ -17,3 +30,85 @@ pub fn hash(buffer: &[u8]) -> String {
     m.update(buffer);
     m.digest().to_string()
 }
+
+/// Copies the osrs cache into a scratch directory so its index files can be tampered with.
+///
+/// The data file is linked instead of copied when possible, it must not be modified.
+#[allow(dead_code)]
+pub fn osrs_cache_copy(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    for entry in fs::read_dir("./data/osrs_cache").unwrap() {
+        let path = entry.unwrap().path();
+        let dest = dir.join(path.file_name().unwrap());
+
+        let is_data = path.extension().is_some_and(|ext| ext == "dat2");
+        if !(is_data && fs::hard_link(&path, &dest).is_ok()) {
+            fs::copy(&path, &dest).unwrap();
+        }
+    }
+
+    dir
+}
+
+/// Writes a cache that only contains the given encoded archives, to be layered on top of
+/// the osrs cache as an overlay.
+///
+/// Every other archive of the written indices is left empty, as is the entry of every
+/// written index in the reference table, so those are read from the base cache.
+#[allow(dead_code)]
+pub fn write_sparse_cache(name: &str, archives: &[(u8, u32, &[u8])]) -> PathBuf {
+    const SECTOR_SIZE: usize = 520;
+    const HEADER_SIZE: usize = 8;
+
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    // Sector 0 is never used.
+    let mut data = vec![0; SECTOR_SIZE];
+    let mut indices: BTreeMap<u8, Vec<u8>> = BTreeMap::new();
+
+    for &(index_id, archive_id, buffer) in archives {
+        let archive_id_bytes = u16::try_from(archive_id).unwrap().to_be_bytes();
+        let first_sector = data.len() / SECTOR_SIZE;
+
+        let chunks = buffer.chunks(SECTOR_SIZE - HEADER_SIZE);
+        let count = chunks.len();
+        for (chunk, block) in chunks.enumerate() {
+            let next = if chunk + 1 == count {
+                0
+            } else {
+                first_sector + chunk + 1
+            };
+
+            data.extend_from_slice(&archive_id_bytes);
+            data.extend_from_slice(&(chunk as u16).to_be_bytes());
+            data.extend_from_slice(&(next as u32).to_be_bytes()[1..]);
+            data.push(index_id);
+            data.extend_from_slice(block);
+            data.resize(data.len().next_multiple_of(SECTOR_SIZE), 0);
+        }
+
+        let entry_offset = archive_id as usize * 6;
+        let index = indices.entry(index_id).or_default();
+        index.resize(index.len().max(entry_offset + 6), 0);
+        index[entry_offset..entry_offset + 3]
+            .copy_from_slice(&(buffer.len() as u32).to_be_bytes()[1..]);
+        index[entry_offset + 3..entry_offset + 6]
+            .copy_from_slice(&(first_sector as u32).to_be_bytes()[1..]);
+    }
+
+    let last_index = indices.keys().max().copied().unwrap_or(0);
+    let reference_table = vec![0; (usize::from(last_index) + 1) * 6];
+
+    fs::write(dir.join("main_file_cache.dat2"), data).unwrap();
+    fs::write(dir.join("main_file_cache.idx255"), reference_table).unwrap();
+    for (index_id, index) in indices {
+        fs::write(dir.join(format!("main_file_cache.idx{index_id}")), index).unwrap();
+    }
+
+    dir
+}